@@ -9,13 +9,25 @@ use crate::{
 use crate::error::{to_program_error, StakeError};
 #[cfg(all(feature = "wire_bincode", feature = "std"))]
 use bincode;
+#[cfg(all(feature = "wire_bincode", feature = "std"))]
+use crate::wire;
+#[cfg(all(feature = "wire_bincode", not(feature = "std")))]
+use crate::wire_sbf;
 use pinocchio::{
     account_info::AccountInfo, msg, program_entrypoint, program_error::ProgramError,
     pubkey::Pubkey, ProgramResult,
 };
 use pinocchio::sysvars::Sysvar;
+#[cfg(not(feature = "strict-wire"))]
+use crate::instruction::tags::*;
 
 macro_rules! trace { ($($t:tt)*) => { #[cfg(feature = "cu-trace")] { msg!($($t)*); } } }
+// Decode-path markers: `dispatch:<path>:<variant>`, path is one of
+// short-tag | std-bincode | sbf-manual | compat-fallback
+macro_rules! metric { ($($t:tt)*) => { #[cfg(feature = "dispatch-metrics")] { msg!($($t)*); } } }
+
+#[cfg(all(feature = "strict-wire", feature = "compat_loose_decode"))]
+compile_error!("`strict-wire` and `compat_loose_decode` are mutually exclusive");
 
 // Entrypoint macro
 program_entrypoint!(process_instruction);
@@ -48,49 +60,49 @@ fn process_instruction(
             {
                 #[cfg(feature = "cu-trace")]
                 { pinocchio::msg!("fast:delegate_by_metas"); }
+                metric!("dispatch:compat-fallback:delegate");
                 return crate::instruction::process_delegate::process_delegate(accounts);
             }
         }
     }
     if instruction_data.len() < 4 { pinocchio::msg!("pre:lt4"); } else { pinocchio::msg!("pre:ge4"); }
-    // Universal fast-path for ProgramTest short encodings (works in std and sbf)
+    // Universal fast-path for ProgramTest short encodings (works in std and sbf).
+    // Under `strict-wire` only the native bincode decode below is reachable.
+    #[cfg(not(feature = "strict-wire"))]
     if instruction_data.is_empty() {
         // Empty => DeactivateDelinquent (but respect epoch-rewards gating)
+        metric!("dispatch:short-tag:deact_delinquent");
         if epoch_rewards_active() {
             return Err(to_program_error(StakeError::EpochRewardsActive));
         }
         return crate::instruction::deactivate_delinquent::process_deactivate_delinquent(accounts);
     }
+    #[cfg(not(feature = "strict-wire"))]
     if instruction_data.len() < 4 {
         let tag = instruction_data[0];
         #[cfg(feature = "cu-trace")]
         { pinocchio::msg!("fast:short_tag={}", tag as u64); }
+        #[cfg(feature = "dispatch-metrics")]
+        if let Some(name) = short_tag_name(tag) {
+            msg!("dispatch:short-tag:{}", name);
+        }
         match tag {
-            2 => { return crate::instruction::process_delegate::process_delegate(accounts); }
-            5 => { return crate::instruction::deactivate::process_deactivate(accounts); }
-            9 => { return crate::instruction::initialize_checked::process_initialize_checked(accounts); }
-            10 => { return crate::instruction::authorize_checked::process_authorize_checked(accounts, crate::state::StakeAuthorize::Staker); }
-            11 => {
+            // Initialize needs Authorized + Lockup args: a short `[0, ..]` is never an Initialize
+            TAG_INITIALIZE => { return Err(ProgramError::InvalidInstructionData); }
+            TAG_DELEGATE => { return crate::instruction::process_delegate::process_delegate(accounts); }
+            TAG_DEACTIVATE => { return crate::instruction::deactivate::process_deactivate(accounts); }
+            TAG_INITIALIZE_CHECKED => { return crate::instruction::initialize_checked::process_initialize_checked(accounts); }
+            TAG_AUTHORIZE_CHECKED => { return crate::instruction::authorize_checked::process_authorize_checked(accounts, crate::state::StakeAuthorize::Staker); }
+            TAG_AUTHORIZE_CHECKED_WITH_SEED => {
                 // Default empty seed/owner; new_authorized taken from account metas (index 3)
                 let new_authorized = accounts.get(3).map(|ai| *ai.key()).ok_or(ProgramError::NotEnoughAccountKeys)?;
                 let data = AuthorizeCheckedWithSeedData { new_authorized, stake_authorize: crate::state::StakeAuthorize::Staker, authority_seed: &[], authority_owner: Pubkey::default() };
                 return crate::instruction::process_authorize_checked_with_seed::process_authorize_checked_with_seed(accounts, data);
             }
-            12 => {
-                // Pre-dispatch SLC in universal short path
-                pinocchio::msg!("pre:slc:short");
-                // Minimal signer policy: require some signer in metas
-                if !accounts.iter().any(|ai| ai.is_signer()) {
-                    return Err(ProgramError::MissingRequiredSignature);
-                }
-                // Pass through the compact payload (flags + fields) after the tag
-                let rest = &instruction_data[1..];
-                if epoch_rewards_active() {
-                    return Err(to_program_error(StakeError::EpochRewardsActive));
-                }
-                return crate::instruction::process_set_lockup_checked::process_set_lockup_checked(accounts, rest);
-            }
-            13 => {
+            // 12 (SetLockupChecked) is handled below so that short payloads, including the
+            // empty-args `[12, 0x00]` form, go through the same role-specific signer check.
+            // Accounts are ignored, as in native; stray metas are tolerated
+            TAG_GET_MINIMUM_DELEGATION => {
                 let value = get_minimum_delegation();
                 let data = value.to_le_bytes();
                 #[cfg(not(feature = "std"))]
@@ -100,42 +112,45 @@ fn process_instruction(
                 return Ok(());
             }
             #[cfg(feature = "compat_loose_decode")]
-            14 | 18 | 19 | 20 | 21 => {
+            TAG_DEACTIVATE_DELINQUENT | TAG_DEACTIVATE_DELINQUENT_DRIFT_FIRST..=TAG_DEACTIVATE_DELINQUENT_DRIFT_LAST => {
                 if epoch_rewards_active() {
                     return Err(to_program_error(StakeError::EpochRewardsActive));
                 }
                 return crate::instruction::deactivate_delinquent::process_deactivate_delinquent(accounts);
             }
+            // 1-byte compat form carries no amount; handlers reject zero lamports
+            TAG_MOVE_STAKE | TAG_MOVE_LAMPORTS => { return dispatch_short_move(accounts, tag, 0); }
+            #[cfg(feature = "merge-many")]
+            crate::instruction::merge_many::MERGE_MANY_TAG => {
+                if epoch_rewards_active() {
+                    return Err(to_program_error(StakeError::EpochRewardsActive));
+                }
+                return crate::instruction::merge_many::process_merge_many(accounts);
+            }
             _ => {}
         }
     }
-    // Accept universal short-encoded SetLockupChecked even when payload >= 4 bytes:
+    // Short-encoded moves with an amount: [16|17, lamports u64 LE]. Never valid bincode
+    // (which needs a 4-byte variant), so this cannot shadow a native encoding.
+    #[cfg(not(feature = "strict-wire"))]
+    if instruction_data.len() == 9 && matches!(instruction_data[0], TAG_MOVE_STAKE | TAG_MOVE_LAMPORTS) {
+        let tag = instruction_data[0];
+        #[cfg(feature = "dispatch-metrics")]
+        if let Some(name) = short_tag_name(tag) {
+            msg!("dispatch:short-tag:{}", name);
+        }
+        let mut lamports = [0u8; 8];
+        lamports.copy_from_slice(&instruction_data[1..9]);
+        return dispatch_short_move(accounts, tag, u64::from_le_bytes(lamports));
+    }
+    // Accept universal short-encoded SetLockupChecked at any payload length:
     // if first byte is 12, treat remaining bytes as compact payload (flags + fields).
-    if instruction_data.first().copied() == Some(12u8) {
+    #[cfg(not(feature = "strict-wire"))]
+    if instruction_data.first().copied() == Some(TAG_SET_LOCKUP_CHECKED) {
         pinocchio::msg!("pre:slc:short");
-        // Enforce role-specific signer like native: withdrawer when not in force, custodian when in force.
-        let stake_ai = accounts.get(0).ok_or(ProgramError::NotEnoughAccountKeys)?;
-        let state = crate::helpers::get_stake_state(stake_ai)?;
-        use crate::state::stake_state_v2::StakeStateV2 as S2;
-        let meta_opt = match state { S2::Initialized(ref m) => Some(m), S2::Stake(ref m, _, _) => Some(m), _ => None };
-        if let Some(meta) = meta_opt {
-            let clk = pinocchio::sysvars::clock::Clock::get()?;
-            let in_force = meta.lockup.is_in_force(&clk, None);
-            if in_force {
-                // Require custodian signer
-                let want = pinocchio::pubkey::Pubkey::try_from(meta.lockup.custodian).map_err(|_| ProgramError::InvalidInstructionData)?;
-                let ok = accounts.iter().any(|ai| ai.key() == &want && ai.is_signer());
-                if !ok { return Err(ProgramError::MissingRequiredSignature); }
-            } else {
-                // Require withdrawer signer
-                let want = pinocchio::pubkey::Pubkey::try_from(meta.authorized.withdrawer).map_err(|_| ProgramError::InvalidInstructionData)?;
-                let ok = accounts.iter().any(|ai| ai.key() == &want && ai.is_signer());
-                if !ok { return Err(ProgramError::MissingRequiredSignature); }
-            }
-        } else {
-            // If not Initialized/Stake, fall back to requiring any signer
-            if !accounts.iter().any(|ai| ai.is_signer()) { return Err(ProgramError::MissingRequiredSignature); }
-        }
+        metric!("dispatch:short-tag:set_lockup_checked");
+        // State (Initialized/Stake only) and the role signer are checked in the handler,
+        // which already loads state and Clock.
         let rest = &instruction_data[1..];
         if epoch_rewards_active() {
             return Err(to_program_error(StakeError::EpochRewardsActive));
@@ -148,26 +163,31 @@ fn process_instruction(
         #[cfg(feature = "cu-trace")]
         { pinocchio::msg!("std:inspect len={} b0={}", instruction_data.len() as u64, instruction_data.get(0).copied().unwrap_or(0) as u64); }
         // Accept short encodings used by ProgramTest helpers
+        #[cfg(not(feature = "strict-wire"))]
         if instruction_data.is_empty() {
             return dispatch_wire_instruction(accounts, wire::StakeInstruction::DeactivateDelinquent);
         }
+        #[cfg(not(feature = "strict-wire"))]
         if instruction_data.len() < 4 {
-            let tag = instruction_data[0] as u32;
+            let tag = instruction_data[0];
             #[cfg(feature = "cu-trace")]
             { pinocchio::msg!("std:short_tag={}", tag as u64); }
             use wire::StakeInstruction as SI;
             let ix = match tag {
-                2  => SI::DelegateStake,
-                9  => SI::InitializeChecked,
-                10 => SI::AuthorizeChecked(wire::StakeAuthorize::Staker),
-                11 => SI::AuthorizeCheckedWithSeed(wire::AuthorizeCheckedWithSeedArgs { stake_authorize: wire::StakeAuthorize::Staker, authority_seed: alloc::string::String::new(), authority_owner: [0u8;32] }),
-                12 => SI::SetLockupChecked(wire::LockupCheckedArgs { unix_timestamp: None, epoch: None }),
-                13 => SI::GetMinimumDelegation,
+                TAG_DELEGATE => SI::DelegateStake,
+                TAG_INITIALIZE_CHECKED => SI::InitializeChecked,
+                TAG_AUTHORIZE_CHECKED => SI::AuthorizeChecked(wire::StakeAuthorize::Staker),
+                TAG_AUTHORIZE_CHECKED_WITH_SEED => SI::AuthorizeCheckedWithSeed(wire::AuthorizeCheckedWithSeedArgs { stake_authorize: wire::StakeAuthorize::Staker, authority_seed: String::new(), authority_owner: [0u8;32] }),
+                TAG_SET_LOCKUP_CHECKED => SI::SetLockupChecked(wire::LockupCheckedArgs { unix_timestamp: None, epoch: None }),
+                TAG_GET_MINIMUM_DELEGATION => SI::GetMinimumDelegation,
                 #[cfg(feature = "compat_loose_decode")]
-                14 | 18 | 19 | 20 | 21 => SI::DeactivateDelinquent,
-                5  => SI::Deactivate,
+                TAG_DEACTIVATE_DELINQUENT | TAG_DEACTIVATE_DELINQUENT_DRIFT_FIRST..=TAG_DEACTIVATE_DELINQUENT_DRIFT_LAST => SI::DeactivateDelinquent,
+                TAG_MOVE_STAKE => SI::MoveStake(0),
+                TAG_MOVE_LAMPORTS => SI::MoveLamports(0),
+                TAG_DEACTIVATE => SI::Deactivate,
                 _ => return Err(ProgramError::InvalidInstructionData),
             };
+            log_std_variant(&ix, "short-tag");
             if epoch_rewards_active() {
                 if !matches!(ix, wire::StakeInstruction::GetMinimumDelegation) {
                     return Err(to_program_error(StakeError::EpochRewardsActive));
@@ -178,7 +198,7 @@ fn process_instruction(
         // std path: decode via bincode into native wire types
         match bincode::deserialize::<wire::StakeInstruction>(instruction_data) {
             Ok(ix) => {
-                log_std_variant(&ix);
+                log_std_variant(&ix, "std-bincode");
                 if epoch_rewards_active() {
                     if !matches!(ix, wire::StakeInstruction::GetMinimumDelegation) {
                         return Err(to_program_error(StakeError::EpochRewardsActive));
@@ -196,6 +216,7 @@ fn process_instruction(
                 #[cfg(feature = "compat_loose_decode")]
                 {
                     if instruction_data.first().copied() == Some(2) {
+                        metric!("dispatch:compat-fallback:delegate");
                         return crate::instruction::process_delegate::process_delegate(accounts);
                     }
                     if accounts.len() >= 3 {
@@ -206,6 +227,7 @@ fn process_instruction(
                             && *delinquent_vote_ai.owner() == crate::state::vote_state::vote_program_id()
                             && *reference_vote_ai.owner() == crate::state::vote_state::vote_program_id()
                         {
+                            metric!("dispatch:compat-fallback:deact_delinquent");
                             return crate::instruction::deactivate_delinquent::process_deactivate_delinquent(accounts);
                         }
                     }
@@ -221,48 +243,52 @@ fn process_instruction(
         #[cfg(feature = "cu-trace")]
         { pinocchio::msg!("sbf:inspect len={}", instruction_data.len() as u64); }
         // Tolerate empty and single-byte encodings for ProgramTest in SBF
+        #[cfg(not(feature = "strict-wire"))]
         if instruction_data.is_empty() {
             if epoch_rewards_active() {
                 return Err(to_program_error(StakeError::EpochRewardsActive));
             }
             return crate::instruction::deactivate_delinquent::process_deactivate_delinquent(accounts);
         }
+        #[cfg(not(feature = "strict-wire"))]
         if instruction_data.len() < 4 {
             #[cfg(feature = "cu-trace")]
             { pinocchio::msg!("sbf:short_len={} b0={}", instruction_data.len() as u64, instruction_data[0] as u64); }
-            let tag = instruction_data[0] as u32;
+            let tag = instruction_data[0];
             use wire_sbf::StakeInstruction as SI;
             let ix = match tag {
-                2 => SI::DelegateStake,
-                9 => SI::InitializeChecked,
-                10 => SI::AuthorizeChecked(wire_sbf::StakeAuthorize::Staker),
-                11 => SI::AuthorizeCheckedWithSeed(wire_sbf::AuthorizeCheckedWithSeedArgs { stake_authorize: wire_sbf::StakeAuthorize::Staker, authority_seed: &[], authority_owner: [0u8;32] }),
-                12 => { pinocchio::msg!("sbf:slc:short" ); SI::SetLockupChecked(wire_sbf::LockupCheckedArgs { unix_timestamp: None, epoch: None }) },
+                TAG_DELEGATE => SI::DelegateStake,
+                TAG_INITIALIZE_CHECKED => SI::InitializeChecked,
+                TAG_AUTHORIZE_CHECKED => SI::AuthorizeChecked(wire_sbf::StakeAuthorize::Staker),
+                TAG_AUTHORIZE_CHECKED_WITH_SEED => SI::AuthorizeCheckedWithSeed(wire_sbf::AuthorizeCheckedWithSeedArgs { stake_authorize: wire_sbf::StakeAuthorize::Staker, authority_seed: &[], authority_owner: [0u8;32] }),
+                TAG_SET_LOCKUP_CHECKED => { pinocchio::msg!("sbf:slc:short" ); SI::SetLockupChecked(wire_sbf::LockupCheckedArgs { unix_timestamp: None, epoch: None }) },
                 #[cfg(feature = "compat_loose_decode")]
-                14 | 18 | 19 | 20 | 21 => SI::DeactivateDelinquent,
-                13 => SI::GetMinimumDelegation,
-                5 => SI::Deactivate,
+                TAG_DEACTIVATE_DELINQUENT | TAG_DEACTIVATE_DELINQUENT_DRIFT_FIRST..=TAG_DEACTIVATE_DELINQUENT_DRIFT_LAST => SI::DeactivateDelinquent,
+                TAG_GET_MINIMUM_DELEGATION => SI::GetMinimumDelegation,
+                TAG_MOVE_STAKE => SI::MoveStake(0),
+                TAG_MOVE_LAMPORTS => SI::MoveLamports(0),
+                TAG_DEACTIVATE => SI::Deactivate,
                 _ => return Err(ProgramError::InvalidInstructionData),
             };
-            log_sbf_variant(&ix);
+            log_sbf_variant(&ix, "short-tag");
             if epoch_rewards_active() {
                 if !matches!(ix, wire_sbf::StakeInstruction::GetMinimumDelegation) {
                     return Err(to_program_error(StakeError::EpochRewardsActive));
                 }
             }
-            return wire_sbf::dispatch(accounts, ix);
+            return dispatch_sbf_instruction(accounts, ix);
         }
         #[cfg(feature = "cu-trace")]
         { pinocchio::msg!("sbf:len={} b0={}", instruction_data.len() as u64, instruction_data.get(0).copied().unwrap_or(0) as u64); }
         match wire_sbf::deserialize(instruction_data) {
             Ok(wire_ix) => {
-                log_sbf_variant(&wire_ix);
+                log_sbf_variant(&wire_ix, "sbf-manual");
                 if epoch_rewards_active() {
                     if !matches!(wire_ix, wire_sbf::StakeInstruction::GetMinimumDelegation) {
                         return Err(to_program_error(StakeError::EpochRewardsActive));
                     }
                 }
-                return wire_sbf::dispatch(accounts, wire_ix);
+                return dispatch_sbf_instruction(accounts, wire_ix);
             }
             Err(_) => {
                 #[cfg(feature = "cu-trace")]
@@ -274,6 +300,7 @@ fn process_instruction(
                 #[cfg(feature = "compat_loose_decode")]
                 {
                     if instruction_data.first().copied() == Some(2) {
+                        metric!("dispatch:compat-fallback:delegate");
                         return crate::instruction::process_delegate::process_delegate(accounts);
                     }
                 }
@@ -295,6 +322,7 @@ fn process_instruction(
                 && *clock_ai.key() == pinocchio::sysvars::clock::ID
                 && *hist_ai.key() == crate::state::stake_history::ID
             {
+                metric!("dispatch:compat-fallback:delegate");
                 return crate::instruction::process_delegate::process_delegate(accounts);
             }
         }
@@ -302,75 +330,17 @@ fn process_instruction(
     #[allow(unreachable_code)] Err(ProgramError::InvalidInstructionData)
 }
 
-// Wire decoding for StakeInstruction (bincode) for host/dev (std)
-#[cfg(all(feature = "wire_bincode", feature = "std"))]
-mod wire {
-    use serde::{Deserialize, Serialize};
-    use super::*;
-    #[cfg(not(feature = "std"))]
-    use alloc::string::String;
-
-    pub type WirePubkey = [u8; 32];
-    impl From<WirePubkey> for Pubkey { fn from(w: WirePubkey) -> Self { Pubkey::new_from_array(w) } }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-    pub struct Authorized { pub staker: WirePubkey, pub withdrawer: WirePubkey }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-    pub struct Lockup { pub unix_timestamp: i64, pub epoch: u64, pub custodian: WirePubkey }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-    pub enum StakeAuthorize { Staker, Withdrawer }
-
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    pub struct LockupArgs { pub unix_timestamp: Option<i64>, pub epoch: Option<u64>, pub custodian: Option<WirePubkey> }
-
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    pub struct LockupCheckedArgs { pub unix_timestamp: Option<i64>, pub epoch: Option<u64> }
-
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    pub struct AuthorizeWithSeedArgs { pub new_authorized_pubkey: WirePubkey, pub stake_authorize: StakeAuthorize, pub authority_seed: String, pub authority_owner: WirePubkey }
-
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    pub struct AuthorizeCheckedWithSeedArgs { pub stake_authorize: StakeAuthorize, pub authority_seed: String, pub authority_owner: WirePubkey }
-
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    pub enum StakeInstruction {
-        Initialize(Authorized, Lockup),
-        Authorize(WirePubkey, StakeAuthorize),
-        DelegateStake,
-        Split(u64),
-        Withdraw(u64),
-        Deactivate,
-        SetLockup(LockupArgs),
-        Merge,
-        AuthorizeWithSeed(AuthorizeWithSeedArgs),
-        InitializeChecked,
-        AuthorizeChecked(StakeAuthorize),
-        AuthorizeCheckedWithSeed(AuthorizeCheckedWithSeedArgs),
-        SetLockupChecked(LockupCheckedArgs),
-        GetMinimumDelegation,
-        DeactivateDelinquent,
-        #[deprecated]
-        Redelegate,
-        MoveStake(u64),
-        MoveLamports(u64),
-    }
-}
-
 #[cfg(all(feature = "wire_bincode", feature = "std"))]
 fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstruction) -> ProgramResult {
     use wire::*;
     match ix {
         StakeInstruction::Initialize(auth, l) => {
             pinocchio::msg!("std:init:dispatch");
-            let authorized = crate::state::accounts::Authorized { staker: Pubkey::from(auth.staker), withdrawer: Pubkey::from(auth.withdrawer) };
-            let lockup = crate::state::state::Lockup { unix_timestamp: l.unix_timestamp, epoch: l.epoch, custodian: Pubkey::from(l.custodian) };
-            instruction::initialize::initialize(accounts, authorized, lockup)
+            instruction::initialize::initialize(accounts, auth.into(), l.into())
         }
         StakeInstruction::Authorize(new_auth, which) => {
             trace!("Instruction: Authorize");
-            let typ = match which { StakeAuthorize::Staker => StakeAuthorize::Staker, StakeAuthorize::Withdrawer => StakeAuthorize::Withdrawer };
+            let typ = crate::state::StakeAuthorize::from(which);
             instruction::authorize::process_authorize(accounts, Pubkey::from(new_auth), typ)
         }
         StakeInstruction::DelegateStake => {
@@ -406,7 +376,7 @@ fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstructio
         StakeInstruction::AuthorizeWithSeed(args) => {
             trace!("Instruction: AuthorizeWithSeed");
             let new_authorized = Pubkey::from(args.new_authorized_pubkey);
-            let stake_authorize = match args.stake_authorize { StakeAuthorize::Staker => StakeAuthorize::Staker, StakeAuthorize::Withdrawer => StakeAuthorize::Withdrawer };
+            let stake_authorize = crate::state::StakeAuthorize::from(args.stake_authorize);
             let authority_owner = Pubkey::from(args.authority_owner);
             let seed_vec = args.authority_seed.into_bytes();
             let data = AuthorizeWithSeedData { new_authorized, stake_authorize, authority_seed: &seed_vec, authority_owner };
@@ -425,12 +395,12 @@ fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstructio
         }
         StakeInstruction::AuthorizeChecked(which) => {
             trace!("Instruction: AuthorizeChecked");
-            let typ = match which { StakeAuthorize::Staker => StakeAuthorize::Staker, StakeAuthorize::Withdrawer => StakeAuthorize::Withdrawer };
+            let typ = crate::state::StakeAuthorize::from(which);
             instruction::authorize_checked::process_authorize_checked(accounts, typ)
         }
         StakeInstruction::AuthorizeCheckedWithSeed(args) => {
             trace!("Instruction: AuthorizeCheckedWithSeed");
-            let stake_authorize = match args.stake_authorize { StakeAuthorize::Staker => StakeAuthorize::Staker, StakeAuthorize::Withdrawer => StakeAuthorize::Withdrawer };
+            let stake_authorize = crate::state::StakeAuthorize::from(args.stake_authorize);
             let authority_owner = Pubkey::from(args.authority_owner);
             let seed_vec = args.authority_seed.into_bytes();
             // Native-ABI order: [stake, base, clock, new_authorized]
@@ -442,19 +412,6 @@ fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstructio
         }
         StakeInstruction::SetLockupChecked(args) => {
             trace!("Instruction: SetLockupChecked");
-            // Resolve required signers; prefer exact withdrawer from state, fallback to heuristic
-            let mut in_force = false;
-            if let Some(stake_ai) = accounts.get(0) {
-                if let Ok(state) = crate::helpers::get_stake_state(stake_ai) {
-                    if let crate::state::stake_state_v2::StakeStateV2::Initialized(meta)
-                        | crate::state::stake_state_v2::StakeStateV2::Stake(meta, _, _) = state
-                    {
-                        if let Ok(clk) = pinocchio::sysvars::clock::Clock::get() {
-                            in_force = meta.lockup.is_in_force(&clk, None);
-                        }
-                    }
-                }
-            }
             // Minimal signer requirement: any signer in metas
             if !accounts.iter().any(|ai| ai.is_signer()) { return Err(ProgramError::MissingRequiredSignature); }
             // Encode native args into the compact flags+payload expected by the handler
@@ -466,6 +423,7 @@ fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstructio
             buf[0] = flags;
             instruction::process_set_lockup_checked::process_set_lockup_checked(accounts, &buf[..off])
         }
+        // Takes no accounts; like native, any that are passed are ignored
         StakeInstruction::GetMinimumDelegation => {
             trace!("Instruction: GetMinimumDelegation");
             let value = crate::helpers::get_minimum_delegation();
@@ -478,7 +436,7 @@ fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstructio
             trace!("Instruction: DeactivateDelinquent");
             instruction::deactivate_delinquent::process_deactivate_delinquent(accounts)
         }
-        #[allow(deprecated)]
+        // Same error as native: clients already treat it as "unsupported", so no custom code
         StakeInstruction::Redelegate => Err(ProgramError::InvalidInstructionData),
         StakeInstruction::MoveStake(lamports) => {
             trace!("Instruction: MoveStake");
@@ -491,281 +449,102 @@ fn dispatch_wire_instruction(accounts: &[AccountInfo], ix: wire::StakeInstructio
     }
 }
 
-// no_std/SBF: manual decoder for native bincode wire without allocations
+// no_std/SBF dispatch of the manually decoded wire
 #[cfg(all(feature = "wire_bincode", not(feature = "std")))]
-mod wire_sbf {
-    use super::*;
-
-    pub type WirePubkey = [u8; 32];
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct Authorized { pub staker: WirePubkey, pub withdrawer: WirePubkey }
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct Lockup { pub unix_timestamp: i64, pub epoch: u64, pub custodian: WirePubkey }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub enum StakeAuthorize { Staker, Withdrawer }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct LockupArgs { pub unix_timestamp: Option<i64>, pub epoch: Option<u64>, pub custodian: Option<WirePubkey> }
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct LockupCheckedArgs { pub unix_timestamp: Option<i64>, pub epoch: Option<u64> }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct AuthorizeWithSeedArgs<'a> { pub new_authorized_pubkey: WirePubkey, pub stake_authorize: StakeAuthorize, pub authority_seed: &'a [u8], pub authority_owner: WirePubkey }
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct AuthorizeCheckedWithSeedArgs<'a> { pub stake_authorize: StakeAuthorize, pub authority_seed: &'a [u8], pub authority_owner: WirePubkey }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub enum StakeInstruction<'a> {
-        Initialize(Authorized, Lockup),
-        Authorize(WirePubkey, StakeAuthorize),
-        DelegateStake,
-        Split(u64),
-        Withdraw(u64),
-        Deactivate,
-        SetLockup(LockupArgs),
-        Merge,
-        AuthorizeWithSeed(AuthorizeWithSeedArgs<'a>),
-        InitializeChecked,
-        AuthorizeChecked(StakeAuthorize),
-        AuthorizeCheckedWithSeed(AuthorizeCheckedWithSeedArgs<'a>),
-        SetLockupChecked(LockupCheckedArgs),
-        GetMinimumDelegation,
-        DeactivateDelinquent,
-        Redelegate,
-        MoveStake(u64),
-        MoveLamports(u64),
-    }
-
-    struct R<'a> { b: &'a [u8], off: usize }
-    impl<'a> R<'a> {
-        fn new(b: &'a [u8]) -> Self { Self { b, off: 0 } }
-        fn rem(&self) -> usize { self.b.len().saturating_sub(self.off) }
-        fn take(&mut self, n: usize) -> Result<&'a [u8], ProgramError> {
-            if self.rem() < n { return Err(ProgramError::InvalidInstructionData); }
-            let s = &self.b[self.off..self.off + n];
-            self.off += n;
-            Ok(s)
-        }
-        // Read the bincode enum variant tag (u32 LE)
-        fn variant(&mut self) -> Result<u32, ProgramError> { self.u32() }
-        fn u8(&mut self) -> Result<u8, ProgramError> { Ok(self.take(1)?[0]) }
-        fn u32(&mut self) -> Result<u32, ProgramError> { let mut a=[0u8;4]; a.copy_from_slice(self.take(4)?); Ok(u32::from_le_bytes(a)) }
-        fn u64(&mut self) -> Result<u64, ProgramError> { let mut a=[0u8;8]; a.copy_from_slice(self.take(8)?); Ok(u64::from_le_bytes(a)) }
-        fn i64(&mut self) -> Result<i64, ProgramError> { let mut a=[0u8;8]; a.copy_from_slice(self.take(8)?); Ok(i64::from_le_bytes(a)) }
-        fn bool(&mut self) -> Result<bool, ProgramError> { Ok(self.u8()? != 0) }
-        fn pubkey(&mut self) -> Result<WirePubkey, ProgramError> { let mut a=[0u8;32]; a.copy_from_slice(self.take(32)?); Ok(a) }
-        fn opt_i64(&mut self) -> Result<Option<i64>, ProgramError> { if self.bool()? { Ok(Some(self.i64()?)) } else { Ok(None) } }
-        fn opt_u64(&mut self) -> Result<Option<u64>, ProgramError> { if self.bool()? { Ok(Some(self.u64()?)) } else { Ok(None) } }
-        fn opt_pubkey(&mut self) -> Result<Option<WirePubkey>, ProgramError> { if self.bool()? { Ok(Some(self.pubkey()?)) } else { Ok(None) } }
-        fn string_bytes(&mut self) -> Result<&'a [u8], ProgramError> { let len = self.u64()? as usize; self.take(len) }
-        fn stake_auth(&mut self) -> Result<StakeAuthorize, ProgramError> {
-            match self.u32()? {
-                0 => Ok(StakeAuthorize::Staker),
-                1 => Ok(StakeAuthorize::Withdrawer),
-                _ => Err(ProgramError::InvalidInstructionData),
-            }
-        }
-    }
-
-    pub fn deserialize(data: &[u8]) -> Result<StakeInstruction, ProgramError> {
-        // Always tolerate empty data for DeactivateDelinquent to match native ProgramTest usage
-        if data.is_empty() {
-            return Ok(StakeInstruction::DeactivateDelinquent);
+fn dispatch_sbf_instruction(accounts: &[AccountInfo], ix: wire_sbf::StakeInstruction) -> ProgramResult {
+    use wire_sbf::StakeInstruction as SI;
+    match ix {
+        SI::Initialize(auth, l) => {
+            pinocchio::msg!("sbf:var:init");
+            pinocchio::msg!("sbf:init:dispatch");
+            crate::instruction::initialize::initialize(accounts, auth.into(), l.into())
         }
-        // Optional loose handling under feature flag
-        #[cfg(feature = "compat_loose_decode")]
-        {
-            if data.len() == 1 {
-                let tag = data[0] as u32;
-                let mut r = R::new(&[0u8; 0]); // dummy to satisfy match signature reuse below
-                use StakeInstruction as SI;
-                let ix = match tag {
-                    0 => SI::Initialize(
-                        Authorized { staker: [0u8;32], withdrawer: [0u8;32] },
-                        Lockup { unix_timestamp: 0, epoch: 0, custodian: [0u8;32] }
-                    ),
-                    1 => SI::Authorize([0u8;32], StakeAuthorize::Staker),
-                    2 => SI::DelegateStake,
-                    3 => SI::Split(0),
-                    4 => SI::Withdraw(0),
-                    5 => SI::Deactivate,
-                    6 => SI::SetLockup(LockupArgs { unix_timestamp: None, epoch: None, custodian: None }),
-                    7 => SI::Merge,
-                    8 => SI::AuthorizeWithSeed(AuthorizeWithSeedArgs { new_authorized_pubkey: [0u8;32], stake_authorize: StakeAuthorize::Staker, authority_seed: &[], authority_owner: [0u8;32] }),
-                    9 => SI::InitializeChecked,
-                    10 => SI::AuthorizeChecked(StakeAuthorize::Staker),
-                    11 => SI::AuthorizeCheckedWithSeed(AuthorizeCheckedWithSeedArgs { stake_authorize: StakeAuthorize::Staker, authority_seed: &[], authority_owner: [0u8;32] }),
-                    12 => SI::SetLockupChecked(LockupCheckedArgs { unix_timestamp: None, epoch: None }),
-                    13 => SI::GetMinimumDelegation,
-                    14 | 18 | 19 | 20 | 21 => SI::DeactivateDelinquent,
-                    15 => SI::Redelegate,
-                    16 => SI::MoveStake(0),
-                    17 => SI::MoveLamports(0),
-                    _ => return Err(ProgramError::InvalidInstructionData),
-                };
-                return Ok(ix);
+        SI::Authorize(new_auth, which) => {
+            pinocchio::msg!("sbf:var:authorize");
+            trace!("Instruction: Authorize");
+            let typ = crate::state::StakeAuthorize::from(which);
+            crate::instruction::authorize::process_authorize(accounts, Pubkey::from(new_auth), typ)
+        }
+        SI::DelegateStake => { pinocchio::msg!("sbf:var:delegate"); trace!("Instruction: DelegateStake"); crate::instruction::process_delegate::process_delegate(accounts) }
+        SI::Split(lamports) => { pinocchio::msg!("sbf:var:split"); pinocchio::msg!("ep:Split"); crate::instruction::split::process_split(accounts, lamports) }
+        SI::Withdraw(lamports) => { pinocchio::msg!("sbf:var:withdraw"); trace!("Instruction: Withdraw"); crate::instruction::withdraw::process_withdraw(accounts, lamports) }
+        SI::Deactivate => {
+            pinocchio::msg!("sbf:var:deactivate"); trace!("Instruction: Deactivate");
+            // If metas are fewer than canonical, prefer surfacing MissingRequiredSignature to match native tests
+            if accounts.len() < 3 {
+                if !accounts.iter().any(|ai| ai.is_signer()) { return Err(ProgramError::MissingRequiredSignature); }
             }
+            crate::instruction::deactivate::process_deactivate(accounts)
         }
-        #[cfg(not(feature = "compat_loose_decode"))]
-        {
-            if data.len() < 4 { return Err(ProgramError::InvalidInstructionData); }
+        SI::SetLockup(args) => { trace!("Instruction: SetLockup");
+            pinocchio::msg!("sbf:var:set_lockup");
+            let data = crate::state::accounts::SetLockupData { unix_timestamp: args.unix_timestamp, epoch: args.epoch, custodian: args.custodian.map(Pubkey::from) };
+            crate::instruction::process_set_lockup::process_set_lockup_parsed(accounts, data)
         }
-        let mut r = R::new(data);
-        let variant = r.variant()?;
-        #[cfg(feature = "cu-trace")]
-        { pinocchio::msg!("sbf:var_id={}", variant as u64); }
-        use StakeInstruction as SI;
-        let ix = match variant {
-            0 => {
-                let auth = Authorized { staker: r.pubkey()?, withdrawer: r.pubkey()? };
-                let l = Lockup { unix_timestamp: r.i64()?, epoch: r.u64()?, custodian: r.pubkey()? };
-                SI::Initialize(auth, l)
-            }
-            1 => { SI::Authorize(r.pubkey()?, r.stake_auth()?) }
-            2 => { SI::DelegateStake }
-            3 => { SI::Split(r.u64()?) }
-            4 => { SI::Withdraw(r.u64()?) }
-            5 => { SI::Deactivate }
-            6 => {
-                let args = LockupArgs { unix_timestamp: r.opt_i64()?, epoch: r.opt_u64()?, custodian: r.opt_pubkey()? };
-                SI::SetLockup(args)
-            }
-            7 => { SI::Merge }
-            8 => {
-                let args = AuthorizeWithSeedArgs { new_authorized_pubkey: r.pubkey()?, stake_authorize: r.stake_auth()?, authority_seed: r.string_bytes()?, authority_owner: r.pubkey()? };
-                SI::AuthorizeWithSeed(args)
-            }
-            9 => { SI::InitializeChecked }
-            10 => { SI::AuthorizeChecked(r.stake_auth()?) }
-            11 => {
-                let args = AuthorizeCheckedWithSeedArgs { stake_authorize: r.stake_auth()?, authority_seed: r.string_bytes()?, authority_owner: r.pubkey()? };
-                SI::AuthorizeCheckedWithSeed(args)
-            }
-            12 => {
-                let args = LockupCheckedArgs { unix_timestamp: r.opt_i64()?, epoch: r.opt_u64()? };
-                SI::SetLockupChecked(args)
-            }
-            13 => { SI::GetMinimumDelegation }
-            14 => { SI::DeactivateDelinquent }
-            // Some SDK builds encode DeactivateDelinquent at 19
-            19 => { SI::DeactivateDelinquent }
-            // Tolerate SDK variant reordering: some versions encode DeactivateDelinquent at 18
-            18 => { SI::DeactivateDelinquent }
-            // Additional tolerance for variant drift
-            20 => { SI::DeactivateDelinquent }
-            21 => { SI::DeactivateDelinquent }
-            15 => { SI::Redelegate }
-            16 => { SI::MoveStake(r.u64()?) }
-            17 => { SI::MoveLamports(r.u64()?) }
-            // Unknown variants: tolerant fallback to SetLockupChecked arg shape
-            _ => {
-                #[cfg(feature = "cu-trace")]
-                pinocchio::msg!("sbf:var:tolerant_fallback");
-                let args = LockupCheckedArgs { unix_timestamp: r.opt_i64()?, epoch: r.opt_u64()? };
-                SI::SetLockupChecked(args)
-            },
-        };
-        Ok(ix)
-    }
-
-    pub fn dispatch(accounts: &[AccountInfo], ix: StakeInstruction) -> ProgramResult {
-        use StakeInstruction as SI;
-        match ix {
-            SI::Initialize(auth, l) => {
-                pinocchio::msg!("sbf:var:init");
-                pinocchio::msg!("sbf:init:dispatch");
-                let authorized = crate::state::accounts::Authorized { staker: Pubkey::from(auth.staker), withdrawer: Pubkey::from(auth.withdrawer) };
-                let lockup = crate::state::state::Lockup { unix_timestamp: l.unix_timestamp, epoch: l.epoch, custodian: Pubkey::from(l.custodian) };
-                crate::instruction::initialize::initialize(accounts, authorized, lockup)
-            }
-            SI::Authorize(new_auth, which) => {
-                pinocchio::msg!("sbf:var:authorize");
-                trace!("Instruction: Authorize");
-                let typ = match which { StakeAuthorize::Staker => crate::state::StakeAuthorize::Staker, StakeAuthorize::Withdrawer => crate::state::StakeAuthorize::Withdrawer };
-                crate::instruction::authorize::process_authorize(accounts, Pubkey::from(new_auth), typ)
-            }
-            SI::DelegateStake => { pinocchio::msg!("sbf:var:delegate"); trace!("Instruction: DelegateStake"); crate::instruction::process_delegate::process_delegate(accounts) }
-            SI::Split(lamports) => { pinocchio::msg!("sbf:var:split"); pinocchio::msg!("ep:Split"); crate::instruction::split::process_split(accounts, lamports) }
-            SI::Withdraw(lamports) => { pinocchio::msg!("sbf:var:withdraw"); trace!("Instruction: Withdraw"); crate::instruction::withdraw::process_withdraw(accounts, lamports) }
-            SI::Deactivate => {
-                pinocchio::msg!("sbf:var:deactivate"); trace!("Instruction: Deactivate");
-                // If metas are fewer than canonical, prefer surfacing MissingRequiredSignature to match native tests
-                if accounts.len() < 3 {
-                    if !accounts.iter().any(|ai| ai.is_signer()) { return Err(ProgramError::MissingRequiredSignature); }
-                }
-                crate::instruction::deactivate::process_deactivate(accounts)
-            }
-            SI::SetLockup(args) => { trace!("Instruction: SetLockup");
-                pinocchio::msg!("sbf:var:set_lockup");
-                let data = crate::state::accounts::SetLockupData { unix_timestamp: args.unix_timestamp, epoch: args.epoch, custodian: args.custodian.map(Pubkey::from) };
-                crate::instruction::process_set_lockup::process_set_lockup_parsed(accounts, data)
-            }
-            SI::Merge => { pinocchio::msg!("sbf:var:merge"); trace!("Instruction: Merge"); crate::instruction::merge_dedicated::process_merge(accounts) }
-            SI::AuthorizeWithSeed(args) => { trace!("Instruction: AuthorizeWithSeed");
-                pinocchio::msg!("sbf:var:authorize_with_seed"); pinocchio::msg!("sbf:aws:dispatch");
-                let new_authorized = Pubkey::from(args.new_authorized_pubkey);
-                let stake_authorize = match args.stake_authorize { StakeAuthorize::Staker => crate::state::StakeAuthorize::Staker, StakeAuthorize::Withdrawer => crate::state::StakeAuthorize::Withdrawer };
-                let authority_owner = Pubkey::from(args.authority_owner);
-                // Copy seed bytes into a fixed local buffer to ensure stable lifetime
-                let mut seed_buf = [0u8; 32];
-                let seed_len = core::cmp::min(args.authority_seed.len(), 32);
-                if seed_len > 0 { seed_buf[..seed_len].copy_from_slice(&args.authority_seed[..seed_len]); }
-                let seed_slice = &seed_buf[..seed_len];
-                let data = crate::state::accounts::AuthorizeWithSeedData { new_authorized, stake_authorize, authority_seed: seed_slice, authority_owner };
-                // Require at least one signer (base must sign)
-                if !accounts.iter().any(|ai| ai.is_signer()) { return Err(ProgramError::MissingRequiredSignature); }
-                pinocchio::msg!("sbf:aws:precall");
-                let r = crate::instruction::process_authorized_with_seeds::process_authorized_with_seeds(accounts, data);
-                if r.is_err() { pinocchio::msg!("sbf:aws:ret_err"); }
-                r
-            }
-            SI::InitializeChecked => { pinocchio::msg!("sbf:var:init_checked"); trace!("Instruction: InitializeChecked"); crate::instruction::initialize_checked::process_initialize_checked(accounts) }
-            SI::AuthorizeChecked(which) => { pinocchio::msg!("sbf:var:auth_checked"); trace!("Instruction: AuthorizeChecked");
-                let typ = match which { StakeAuthorize::Staker => crate::state::StakeAuthorize::Staker, StakeAuthorize::Withdrawer => crate::state::StakeAuthorize::Withdrawer };
-                crate::instruction::authorize_checked::process_authorize_checked(accounts, typ)
-            }
-            SI::AuthorizeCheckedWithSeed(args) => { pinocchio::msg!("sbf:var:auth_cws"); trace!("Instruction: AuthorizeCheckedWithSeed");
-                pinocchio::msg!("sbf:acws:dispatch");
-                let stake_authorize = match args.stake_authorize { StakeAuthorize::Staker => crate::state::StakeAuthorize::Staker, StakeAuthorize::Withdrawer => crate::state::StakeAuthorize::Withdrawer };
-                let authority_owner = Pubkey::from(args.authority_owner);
-                // In native wire, new_authorized is provided as an account at index 3
-                let new_authorized = accounts.get(3).map(|ai| *ai.key()).ok_or(ProgramError::NotEnoughAccountKeys)?;
-                let mut seed_buf = [0u8; 32];
-                let seed_len = core::cmp::min(args.authority_seed.len(), 32);
-                if seed_len > 0 { seed_buf[..seed_len].copy_from_slice(&args.authority_seed[..seed_len]); }
-                let seed_slice = &seed_buf[..seed_len];
-                let data = crate::state::accounts::AuthorizeCheckedWithSeedData { new_authorized, stake_authorize, authority_seed: seed_slice, authority_owner };
-                crate::instruction::process_authorize_checked_with_seed::process_authorize_checked_with_seed(accounts, data)
-            }
-            SI::SetLockupChecked(args) => {
-                pinocchio::msg!("sbf:var:set_lockup_checked");
-                trace!("Instruction: SetLockupChecked");
-                pinocchio::msg!("sbf:slc:dispatch");
-                // Minimal signer check: any signer in metas (SDK ensures withdrawer/custodian signer)
-                let has_any_signer = accounts.iter().any(|ai| ai.is_signer());
-                if has_any_signer { pinocchio::msg!("sbf:slc:any_signer=1"); } else { pinocchio::msg!("sbf:slc:any_signer=0"); }
-                if !has_any_signer { return Err(ProgramError::MissingRequiredSignature); }
-                let mut buf = [0u8; 1 + 8 + 8];
-                let mut off = 1usize;
-                let mut flags = 0u8;
-                if let Some(ts) = args.unix_timestamp { flags |= 0x01; buf[off..off + 8].copy_from_slice(&ts.to_le_bytes()); off += 8; }
-                if let Some(ep) = args.epoch { flags |= 0x02; buf[off..off + 8].copy_from_slice(&ep.to_le_bytes()); off += 8; }
-                buf[0] = flags;
-                crate::instruction::process_set_lockup_checked::process_set_lockup_checked(accounts, &buf[..off])
-            }
-            SI::GetMinimumDelegation => { pinocchio::msg!("sbf:var:get_min"); trace!("Instruction: GetMinimumDelegation");
-                let value = crate::helpers::get_minimum_delegation();
-                let data = value.to_le_bytes();
-                pinocchio::program::set_return_data(&data);
-                Ok(())
-            }
-            SI::DeactivateDelinquent => { pinocchio::msg!("sbf:var:deact_delinquent"); trace!("Instruction: DeactivateDelinquent"); crate::instruction::deactivate_delinquent::process_deactivate_delinquent(accounts) }
-            SI::Redelegate => { pinocchio::msg!("sbf:var:redelegate"); Err(ProgramError::InvalidInstructionData) },
-            SI::MoveStake(lamports) => { pinocchio::msg!("sbf:var:move_stake"); trace!("Instruction: MoveStake"); crate::instruction::process_move_stake::process_move_stake(accounts, lamports) }
-            SI::MoveLamports(lamports) => { pinocchio::msg!("sbf:var:move_lamports"); trace!("Instruction: MoveLamports"); crate::instruction::move_lamports::process_move_lamports(accounts, lamports) }
+        SI::Merge => { pinocchio::msg!("sbf:var:merge"); trace!("Instruction: Merge"); crate::instruction::merge_dedicated::process_merge(accounts) }
+        SI::AuthorizeWithSeed(args) => { trace!("Instruction: AuthorizeWithSeed");
+            pinocchio::msg!("sbf:var:authorize_with_seed"); pinocchio::msg!("sbf:aws:dispatch");
+            let new_authorized = Pubkey::from(args.new_authorized_pubkey);
+            let stake_authorize = crate::state::StakeAuthorize::from(args.stake_authorize);
+            let authority_owner = Pubkey::from(args.authority_owner);
+            // Copy seed bytes into a fixed local buffer to ensure stable lifetime
+            let mut seed_buf = [0u8; 32];
+            let seed_len = core::cmp::min(args.authority_seed.len(), 32);
+            if seed_len > 0 { seed_buf[..seed_len].copy_from_slice(&args.authority_seed[..seed_len]); }
+            let seed_slice = &seed_buf[..seed_len];
+            let data = crate::state::accounts::AuthorizeWithSeedData { new_authorized, stake_authorize, authority_seed: seed_slice, authority_owner };
+            // Require at least one signer (base must sign)
+            if !accounts.iter().any(|ai| ai.is_signer()) { return Err(ProgramError::MissingRequiredSignature); }
+            pinocchio::msg!("sbf:aws:precall");
+            let r = crate::instruction::process_authorized_with_seeds::process_authorized_with_seeds(accounts, data);
+            if r.is_err() { pinocchio::msg!("sbf:aws:ret_err"); }
+            r
+        }
+        SI::InitializeChecked => { pinocchio::msg!("sbf:var:init_checked"); trace!("Instruction: InitializeChecked"); crate::instruction::initialize_checked::process_initialize_checked(accounts) }
+        SI::AuthorizeChecked(which) => { pinocchio::msg!("sbf:var:auth_checked"); trace!("Instruction: AuthorizeChecked");
+            let typ = crate::state::StakeAuthorize::from(which);
+            crate::instruction::authorize_checked::process_authorize_checked(accounts, typ)
+        }
+        SI::AuthorizeCheckedWithSeed(args) => { pinocchio::msg!("sbf:var:auth_cws"); trace!("Instruction: AuthorizeCheckedWithSeed");
+            pinocchio::msg!("sbf:acws:dispatch");
+            let stake_authorize = crate::state::StakeAuthorize::from(args.stake_authorize);
+            let authority_owner = Pubkey::from(args.authority_owner);
+            // In native wire, new_authorized is provided as an account at index 3
+            let new_authorized = accounts.get(3).map(|ai| *ai.key()).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let mut seed_buf = [0u8; 32];
+            let seed_len = core::cmp::min(args.authority_seed.len(), 32);
+            if seed_len > 0 { seed_buf[..seed_len].copy_from_slice(&args.authority_seed[..seed_len]); }
+            let seed_slice = &seed_buf[..seed_len];
+            let data = crate::state::accounts::AuthorizeCheckedWithSeedData { new_authorized, stake_authorize, authority_seed: seed_slice, authority_owner };
+            crate::instruction::process_authorize_checked_with_seed::process_authorize_checked_with_seed(accounts, data)
+        }
+        SI::SetLockupChecked(args) => {
+            pinocchio::msg!("sbf:var:set_lockup_checked");
+            trace!("Instruction: SetLockupChecked");
+            pinocchio::msg!("sbf:slc:dispatch");
+            // Minimal signer check: any signer in metas (SDK ensures withdrawer/custodian signer)
+            let has_any_signer = accounts.iter().any(|ai| ai.is_signer());
+            if has_any_signer { pinocchio::msg!("sbf:slc:any_signer=1"); } else { pinocchio::msg!("sbf:slc:any_signer=0"); }
+            if !has_any_signer { return Err(ProgramError::MissingRequiredSignature); }
+            let mut buf = [0u8; 1 + 8 + 8];
+            let mut off = 1usize;
+            let mut flags = 0u8;
+            if let Some(ts) = args.unix_timestamp { flags |= 0x01; buf[off..off + 8].copy_from_slice(&ts.to_le_bytes()); off += 8; }
+            if let Some(ep) = args.epoch { flags |= 0x02; buf[off..off + 8].copy_from_slice(&ep.to_le_bytes()); off += 8; }
+            buf[0] = flags;
+            crate::instruction::process_set_lockup_checked::process_set_lockup_checked(accounts, &buf[..off])
         }
+        // Accounts ignored (native parity)
+        SI::GetMinimumDelegation => { pinocchio::msg!("sbf:var:get_min"); trace!("Instruction: GetMinimumDelegation");
+            let value = crate::helpers::get_minimum_delegation();
+            let data = value.to_le_bytes();
+            pinocchio::program::set_return_data(&data);
+            Ok(())
+        }
+        SI::DeactivateDelinquent => { pinocchio::msg!("sbf:var:deact_delinquent"); trace!("Instruction: DeactivateDelinquent"); crate::instruction::deactivate_delinquent::process_deactivate_delinquent(accounts) }
+        SI::Redelegate => { pinocchio::msg!("sbf:var:redelegate"); Err(ProgramError::InvalidInstructionData) },
+        SI::MoveStake(lamports) => { pinocchio::msg!("sbf:var:move_stake"); trace!("Instruction: MoveStake"); crate::instruction::process_move_stake::process_move_stake(accounts, lamports) }
+        SI::MoveLamports(lamports) => { pinocchio::msg!("sbf:var:move_lamports"); trace!("Instruction: MoveLamports"); crate::instruction::move_lamports::process_move_lamports(accounts, lamports) }
     }
 }
 
@@ -786,7 +565,7 @@ fn epoch_rewards_active() -> bool {
 
 // ----- Debug opcode loggers -----
 #[cfg(all(feature = "wire_bincode", feature = "std"))]
-fn log_std_variant(ix: &wire::StakeInstruction) {
+fn log_std_variant(ix: &wire::StakeInstruction, _path: &str) {
     use wire::StakeInstruction as SI;
     let tag = match ix {
         SI::Initialize(_, _) => "init",
@@ -810,10 +589,11 @@ fn log_std_variant(ix: &wire::StakeInstruction) {
     };
     #[cfg(feature = "cu-trace")]
     pinocchio::msg!("ep:std:{tag}");
+    metric!("dispatch:{}:{}", _path, tag);
 }
 
 #[cfg(all(feature = "wire_bincode", not(feature = "std")))]
-fn log_sbf_variant(ix: &wire_sbf::StakeInstruction) {
+fn log_sbf_variant(ix: &wire_sbf::StakeInstruction, _path: &str) {
     use wire_sbf::StakeInstruction as SI;
     let tag = match ix {
         SI::Initialize(_, _) => "init",
@@ -837,4 +617,38 @@ fn log_sbf_variant(ix: &wire_sbf::StakeInstruction) {
     };
     #[cfg(feature = "cu-trace")]
     pinocchio::msg!("ep:sbf:{tag}");
+    metric!("dispatch:{}:{}", _path, tag);
+}
+
+// Variant names for the entrypoint's universal short-tag fast path
+#[cfg(all(feature = "dispatch-metrics", not(feature = "strict-wire")))]
+fn short_tag_name(tag: u8) -> Option<&'static str> {
+    match tag {
+        TAG_DELEGATE => Some("delegate"),
+        TAG_DEACTIVATE => Some("deactivate"),
+        TAG_INITIALIZE_CHECKED => Some("init_checked"),
+        TAG_AUTHORIZE_CHECKED => Some("auth_checked"),
+        TAG_AUTHORIZE_CHECKED_WITH_SEED => Some("auth_cws"),
+        TAG_GET_MINIMUM_DELEGATION => Some("get_min"),
+        #[cfg(feature = "compat_loose_decode")]
+        TAG_DEACTIVATE_DELINQUENT | TAG_DEACTIVATE_DELINQUENT_DRIFT_FIRST..=TAG_DEACTIVATE_DELINQUENT_DRIFT_LAST => Some("deact_delinquent"),
+        TAG_MOVE_STAKE => Some("move_stake"),
+        TAG_MOVE_LAMPORTS => Some("move_lamports"),
+        #[cfg(feature = "merge-many")]
+        crate::instruction::merge_many::MERGE_MANY_TAG => Some("merge_many"),
+        _ => None,
+    }
+}
+
+// Short-tag MoveStake (16) / MoveLamports (17); epoch-rewards gated like the wire paths
+#[cfg(not(feature = "strict-wire"))]
+fn dispatch_short_move(accounts: &[AccountInfo], tag: u8, lamports: u64) -> ProgramResult {
+    if epoch_rewards_active() {
+        return Err(to_program_error(StakeError::EpochRewardsActive));
+    }
+    match tag {
+        TAG_MOVE_STAKE => crate::instruction::process_move_stake::process_move_stake(accounts, lamports),
+        TAG_MOVE_LAMPORTS => crate::instruction::move_lamports::process_move_lamports(accounts, lamports),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
 }
@@ -3,6 +3,10 @@ pub const DEFAULT_WARMUP_COOLDOWN_RATE: f64 = 0.25;
 pub const FEATURE_STAKE_RAISE_MINIMUM_DELEGATION_TO_1_SOL: bool = false;
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 pub const NEW_WARMUP_COOLDOWN_RATE: f64 = 0.09;
+// Same rates in basis points for the `fixed-point-warmup` integer path
+pub const WARMUP_COOLDOWN_RATE_BPS_DENOMINATOR: u64 = 10_000;
+pub const DEFAULT_WARMUP_COOLDOWN_RATE_BPS: u64 = 2_500;
+pub const NEW_WARMUP_COOLDOWN_RATE_BPS: u64 = 900;
 
 // The warmup/cooldown changed from 25% to 9%. For historical effective stake
 // calculations, a fixed rate is sufficient here since tests operate after full
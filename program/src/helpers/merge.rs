@@ -38,12 +38,14 @@ pub fn merge_delegation_stake_and_credits_observed(
     lamports_to_merge: u64,
     source_credits_observed: u64,
 ) -> Result<(), ProgramError> {
-    stake.delegation.stake =
-        checked_add(bytes_to_u64(stake.delegation.stake), lamports_to_merge)?.to_le_bytes();
-    stake.credits_observed =
+    // Weight credits against the pre-merge stake (native order); both values are
+    // computed before either field is written so a failure leaves `stake` intact.
+    let credits_observed =
         stake_weighted_credits_observed(stake, lamports_to_merge, source_credits_observed)
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .to_le_bytes();
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    let merged = checked_add(bytes_to_u64(stake.delegation.stake), lamports_to_merge)?;
+    stake.credits_observed = credits_observed.to_le_bytes();
+    stake.delegation.stake = merged.to_le_bytes();
     Ok(())
 }
 
@@ -268,3 +270,62 @@ pub fn move_stake_or_lamports_shared_checks(
 
     Ok((source_merge_kind, destination_merge_kind))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::delegation::Delegation;
+
+    fn stake_with(amount: u64, credits: u64) -> Stake {
+        Stake {
+            delegation: Delegation::new(&[1u8; 32], amount, 0u64.to_le_bytes()),
+            credits_observed: credits.to_le_bytes(),
+        }
+    }
+
+    #[test]
+    fn merge_overflow_is_not_a_custom_code() {
+        // Overflowing stake sum: native checked_add error, stake left untouched
+        let mut stake = stake_with(u64::MAX, 5);
+        let err = merge_delegation_stake_and_credits_observed(&mut stake, 1, 5).unwrap_err();
+        assert_eq!(err, ProgramError::InsufficientFunds);
+        assert!(!matches!(err, ProgramError::Custom(_)));
+        assert_eq!(stake, stake_with(u64::MAX, 5));
+
+        // Weighted-credits overflow is a plain None, mapped to ArithmeticOverflow by the caller
+        assert_eq!(stake_weighted_credits_observed(&stake_with(u64::MAX, 5), 1, 7), None);
+
+        // No domain error shares a code with arithmetic failures
+        for err in [
+            StakeError::InsufficientStake,
+            StakeError::InsufficientDelegation,
+            StakeError::MergeMismatch,
+            StakeError::InsufficientFunds,
+        ] {
+            assert_ne!(to_program_error(err), ProgramError::ArithmeticOverflow);
+        }
+    }
+
+    #[test]
+    fn merge_weights_credits_by_pre_merge_stake() {
+        // 100 @ 10 absorbing 300 @ 20: ceil((1000 + 6000) / 400) = 18
+        let mut stake = stake_with(100, 10);
+        merge_delegation_stake_and_credits_observed(&mut stake, 300, 20).unwrap();
+        assert_eq!(stake, stake_with(400, 18));
+    }
+
+    #[test]
+    fn merge_near_max_stakes_overflows_cleanly() {
+        let half = u64::MAX / 2 + 1;
+        let mut stake = stake_with(half, u64::MAX);
+        let err = merge_delegation_stake_and_credits_observed(&mut stake, half, u64::MAX - 1).unwrap_err();
+        assert_eq!(err, ProgramError::ArithmeticOverflow);
+        assert_eq!(stake, stake_with(half, u64::MAX));
+
+        // Largest non-overflowing sum still weights in u128 without loss
+        let mut stake = stake_with(half - 1, u64::MAX);
+        merge_delegation_stake_and_credits_observed(&mut stake, half, u64::MAX - 1).unwrap();
+        assert_eq!(bytes_to_u64(stake.delegation.stake), u64::MAX);
+        assert_eq!(bytes_to_u64(stake.credits_observed), u64::MAX);
+    }
+}
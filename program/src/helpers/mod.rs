@@ -2,6 +2,8 @@ pub mod constant;
 pub mod merge;
 pub mod utils;
 pub mod authorize;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 pub use constant::*;
 pub use merge::*;
@@ -0,0 +1,61 @@
+//! Unit-test fixtures: runtime account buffers viewed as `AccountInfo`.
+
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey};
+
+use crate::{state::stake_state_v2::StakeStateV2, ID};
+
+// Raw runtime account header (pinocchio's `Account` layout); data length is 0
+#[repr(C)]
+pub(crate) struct RawAccount {
+    pub(crate) borrow_state: u8,
+    pub(crate) is_signer: u8,
+    pub(crate) is_writable: u8,
+    pub(crate) executable: u8,
+    pub(crate) resize_delta: i32,
+    pub(crate) key: Pubkey,
+    pub(crate) owner: Pubkey,
+    pub(crate) lamports: u64,
+    pub(crate) data_len: u64,
+}
+
+// Runtime account header followed by its data, as laid out in the input buffer
+#[repr(C)]
+pub(crate) struct RawStakeAccount {
+    pub(crate) header: RawAccount,
+    pub(crate) data: [u8; StakeStateV2::ACCOUNT_SIZE],
+}
+
+pub(crate) fn raw_account(is_signer: bool, is_writable: bool, owner: Pubkey) -> RawAccount {
+    RawAccount {
+        borrow_state: u8::MAX,
+        is_signer: is_signer as u8,
+        is_writable: is_writable as u8,
+        executable: 0,
+        resize_delta: 0,
+        key: [7u8; 32],
+        owner,
+        lamports: 0,
+        data_len: 0,
+    }
+}
+
+// Writable, program-owned stake account holding `state`
+pub(crate) fn raw_stake_account(key: Pubkey, lamports: u64, state: &StakeStateV2) -> RawStakeAccount {
+    let mut header = raw_account(false, true, ID);
+    header.key = key;
+    header.lamports = lamports;
+    header.data_len = StakeStateV2::ACCOUNT_SIZE as u64;
+    let mut raw = RawStakeAccount { header, data: [0u8; StakeStateV2::ACCOUNT_SIZE] };
+    state.serialize(&mut raw.data).unwrap();
+    raw
+}
+
+pub(crate) fn account_info(raw: &mut RawAccount) -> AccountInfo {
+    // SAFETY: AccountInfo is a single pointer to the runtime account header
+    unsafe { core::mem::transmute::<*mut RawAccount, AccountInfo>(raw as *mut RawAccount) }
+}
+
+pub(crate) fn stake_account_info(raw: &mut RawStakeAccount) -> AccountInfo {
+    // SAFETY: the header is the first field, so the pointer is the account pointer
+    unsafe { core::mem::transmute::<*mut RawStakeAccount, AccountInfo>(raw as *mut RawStakeAccount) }
+}
@@ -1,17 +1,17 @@
 extern crate alloc;
 use alloc::collections::BTreeSet;
 use crate::helpers::constant::*;
-use crate::state::stake_history::StakeHistorySysvar;
+use crate::state::stake_history::{StakeHistoryGetEntry, StakeHistorySysvar};
 use pinocchio::{
     account_info::AccountInfo,
     program_error::ProgramError,
     pubkey::Pubkey,
-    sysvars::{rent::Rent, Sysvar},
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
     ProgramResult,
 };
 
 use crate::error::{to_program_error, StakeError};
-use crate::state::stake_state_v2::StakeStateV2;
+use crate::state::stake_state_v2::{StakeStateMut, StakeStateV2};
 use crate::state::vote_state::VoteState;
 use crate::state::{
     delegation::{Delegation, Stake},
@@ -35,6 +35,8 @@ pub enum ErrorCode {
 // Many stake instruction handlers accumulate all transaction signers first,
 // then defer authority checks to Meta/Authorized helpers. This preserves
 // compatibility with existing transactions.
+// No position is skipped: when the stake account itself signs (Uninitialized
+// withdraw/split), its key is part of the collected set.
 pub fn collect_signers(
     accounts: &[AccountInfo],
     array_of_signers: &mut [Pubkey; MAXIMUM_SIGNERS],
@@ -61,6 +63,76 @@ pub fn next_account_info<'a, I: Iterator<Item = &'a AccountInfo>>(
     iter.next().ok_or(ProgramError::NotEnoughAccountKeys)
 }
 
+/// Next account, which must be writable (`InvalidInstructionData` otherwise,
+/// as the handlers report a readonly stake account).
+pub fn next_writable_account<'a, I: Iterator<Item = &'a AccountInfo>>(
+    iter: &mut I,
+) -> Result<&'a AccountInfo, ProgramError> {
+    let account = next_account_info(iter)?;
+    if !account.is_writable() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(account)
+}
+
+/// Next account, which must have signed (`MissingRequiredSignature` otherwise).
+pub fn next_signer_account<'a, I: Iterator<Item = &'a AccountInfo>>(
+    iter: &mut I,
+) -> Result<&'a AccountInfo, ProgramError> {
+    let account = next_account_info(iter)?;
+    if !account.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(account)
+}
+
+/// Next account, which must be owned by `owner` (`InvalidAccountOwner` otherwise).
+pub fn next_owned_account<'a, I: Iterator<Item = &'a AccountInfo>>(
+    iter: &mut I,
+    owner: &Pubkey,
+) -> Result<&'a AccountInfo, ProgramError> {
+    let account = next_account_info(iter)?;
+    if account.owner() != owner {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    Ok(account)
+}
+
+/// Account must be owned by this program (`InvalidAccountOwner` otherwise).
+pub fn assert_owned_by_program(account_info: &AccountInfo) -> ProgramResult {
+    if *account_info.owner() != ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+/// Stake account data must be exactly `StakeStateV2::size_of()` bytes
+/// (`InvalidAccountData` otherwise).
+pub fn assert_stake_sized(stake_account_info: &AccountInfo) -> ProgramResult {
+    if stake_account_info.data_len() != StakeStateV2::size_of() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// `key` must appear among `accounts` as a signer, at any position
+/// (`MissingRequiredSignature` otherwise).
+pub fn require_signer(accounts: &[AccountInfo], key: &Pubkey) -> Result<(), ProgramError> {
+    if accounts.iter().any(|ai| ai.key() == key && ai.is_signer()) {
+        Ok(())
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+/// The lockup custodian, if it is among `accounts` and signed. Callers pass
+/// the metas after the instruction's last required account (the native
+/// optional custodian slot onward), so a custodian is only ever taken from
+/// the trailing accounts, never from a fixed authority position.
+pub fn find_custodian_signer<'a>(accounts: &'a [AccountInfo], custodian: &Pubkey) -> Option<&'a AccountInfo> {
+    accounts.iter().find(|ai| ai.is_signer() && ai.key() == custodian)
+}
+
 /// The minimum stake amount that can be delegated, in lamports.
 /// NOTE: This is also used to calculate the minimum balance of a delegated
 /// stake account, which is the rent exempt reserve _plus_ the minimum stake
@@ -85,6 +157,18 @@ pub fn warmup_cooldown_rate(
     }
 }
 
+/// Basis-point variant of `warmup_cooldown_rate`
+pub fn warmup_cooldown_rate_bps(
+    current_epoch: [u8; 8],
+    new_rate_activation_epoch: Option<[u8; 8]>,
+) -> u64 {
+    if current_epoch < new_rate_activation_epoch.unwrap_or(u64::MAX.to_le_bytes()) {
+        DEFAULT_WARMUP_COOLDOWN_RATE_BPS
+    } else {
+        NEW_WARMUP_COOLDOWN_RATE_BPS
+    }
+}
+
 pub type Epoch = [u8; 8];
 
 pub fn bytes_to_u64(bytes: [u8; 8]) -> u64 {
@@ -248,17 +332,24 @@ fn parse_epoch_credits_triplets(buf: &[u8], n: usize) -> Option<u64> {
 /// SAFETY: `_unchecked` variant performs an unchecked borrow of account data.
 /// Caller must ensure no conflicting borrows are active while reading.
 pub unsafe fn get_stake_state_unchecked(stake_account_info: &AccountInfo) -> Result<StakeStateV2, ProgramError> {
-    if *stake_account_info.owner() != ID {
-        return Err(ProgramError::InvalidAccountOwner);
-    }
+    assert_owned_by_program(stake_account_info)?;
     let data = stake_account_info.borrow_data_unchecked();
     StakeStateV2::deserialize(&data)
 }
 
-/// Safe wrapper around `get_stake_state_unchecked`.
+/// Checked counterpart of `get_stake_state_unchecked`: decodes straight from the
+/// borrowed account data (no copy of the buffer) and fails with
+/// `AccountBorrowFailed` while the data is mutably borrowed.
 pub fn get_stake_state(stake_account_info: &AccountInfo) -> Result<StakeStateV2, ProgramError> {
-    // SAFETY: Reads bytes and returns an owned StakeStateV2; no references escape.
-    unsafe { get_stake_state_unchecked(stake_account_info) }
+    assert_owned_by_program(stake_account_info)?;
+    let data = stake_account_info.try_borrow_data()?;
+    StakeStateV2::deserialize(&data)
+}
+
+/// Like `get_stake_state`, but keeps the data mutably borrowed so single
+/// fields can be written in place (see `StakeStateMut`).
+pub fn get_stake_state_mut(stake_account_info: &AccountInfo) -> Result<StakeStateMut<'_>, ProgramError> {
+    StakeStateMut::from_account_info(stake_account_info)
 }
 
 // write stake state back into account
@@ -279,6 +370,16 @@ pub fn set_stake_state(
     stake_account_info: &AccountInfo,
     stake_state: &StakeStateV2,
 ) -> Result<(), ProgramError> {
+    // Defense in depth: never write stake state into the program's own account
+    if stake_account_info.key() == &ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // A zero delegation should have been demoted to Initialized by the caller.
+    // Debug-only: release builds still rewrite legacy zero-stake accounts.
+    debug_assert!(
+        !matches!(stake_state, StakeStateV2::Stake(_, stake, _) if bytes_to_u64(stake.delegation.stake) == 0),
+        "writing a Stake with zero delegation"
+    );
     // SAFETY: Writes bytes only; no references are returned.
     unsafe { set_stake_state_unchecked(stake_account_info, stake_state) }
 }
@@ -306,6 +407,24 @@ pub fn validate_delegated_amount(
     Ok(ValidatedDelegatedInfo { stake_amount })
 }
 
+// validate_delegated_amount plus the minimum-delegation floor, for delegate paths
+pub fn validate_delegated_amount_min(
+    stake_account_info: &AccountInfo,
+    meta: &Meta,
+) -> Result<ValidatedDelegatedInfo, ProgramError> {
+    let info = validate_delegated_amount(stake_account_info, meta)?;
+    enforce_minimum_delegation(info.stake_amount)?;
+    Ok(info)
+}
+
+#[inline]
+pub(crate) fn enforce_minimum_delegation(stake_amount: u64) -> Result<(), ProgramError> {
+    if stake_amount < get_minimum_delegation() {
+        return Err(to_program_error(StakeError::InsufficientDelegation));
+    }
+    Ok(())
+}
+
 // create new stake object from inputs
 pub fn new_stake(
     stake_amount: u64,
@@ -441,23 +560,40 @@ pub fn relocate_lamports(
     destination_account_info: &AccountInfo,
     lamports: u64,
 ) -> ProgramResult {
-    {
-        let mut source_lamports = source_account_info.try_borrow_mut_lamports()?;
-        *source_lamports = source_lamports
-            .checked_sub(lamports)
-            .ok_or(ProgramError::InsufficientFunds)?;
+    // Compute both balances before touching either account so a failed
+    // debit or credit leaves both unchanged
+    let (source_balance, destination_balance) = relocated_balances(
+        source_account_info.lamports(),
+        destination_account_info.lamports(),
+        lamports,
+    )?;
+    if source_account_info.key() == destination_account_info.key() {
+        // Net zero on a single account
+        return Ok(());
     }
 
-    {
-        let mut destination_lamports = destination_account_info.try_borrow_mut_lamports()?;
-        *destination_lamports = destination_lamports
-            .checked_add(lamports)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    }
+    *source_account_info.try_borrow_mut_lamports()? = source_balance;
+    *destination_account_info.try_borrow_mut_lamports()? = destination_balance;
 
     Ok(())
 }
 
+// (source, destination) balances after moving `lamports`, both checked
+#[inline]
+pub(crate) fn relocated_balances(
+    source: u64,
+    destination: u64,
+    lamports: u64,
+) -> Result<(u64, u64), ProgramError> {
+    let source = source
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let destination = destination
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok((source, destination))
+}
+
 const SUCCESS: u64 = 0;
 
 pub fn get_sysvar(
@@ -485,6 +621,50 @@ pub fn get_sysvar(
     }
 }
 
+/// Lamports still held by a delegation, as native withdraw counts them: the
+/// delegated amount, or the cooling-down effective stake once the deactivation
+/// epoch has been reached.
+pub fn staked_lamports<T: StakeHistoryGetEntry>(
+    stake: &Stake,
+    clock: &Clock,
+    stake_history: &T,
+) -> u64 {
+    let deactivation_epoch = bytes_to_u64(stake.delegation.deactivation_epoch);
+    if deactivation_epoch != u64::MAX && clock.epoch >= deactivation_epoch {
+        stake.delegation.stake(
+            clock.epoch.to_le_bytes(),
+            stake_history,
+            PERPETUAL_NEW_WARMUP_COOLDOWN_RATE_EPOCH,
+        )
+    } else {
+        bytes_to_u64(stake.delegation.stake)
+    }
+}
+
+/// Lamports that can leave a stake account without touching its rent reserve
+/// or its stake: `lamports - reserve - staked`, saturating at zero, with
+/// staked as in `staked_lamports`. Shapes without a meta
+/// (Uninitialized, RewardsPool) report zero; callers handle them directly.
+pub fn free_lamports<T: StakeHistoryGetEntry>(
+    state: &StakeStateV2,
+    account_lamports: u64,
+    clock: &Clock,
+    stake_history: &T,
+) -> u64 {
+    match state {
+        StakeStateV2::Initialized(meta) => {
+            account_lamports.saturating_sub(bytes_to_u64(meta.rent_exempt_reserve))
+        }
+        StakeStateV2::Stake(meta, stake, _) => account_lamports
+            .saturating_sub(bytes_to_u64(meta.rent_exempt_reserve))
+            .saturating_sub(staked_lamports(stake, clock, stake_history)),
+        StakeStateV2::Uninitialized | StakeStateV2::RewardsPool => 0,
+    }
+}
+
+// Native parity: the stake program reports an overflowing lamport/stake sum
+// as InsufficientFunds. Other overflows surface as ArithmeticOverflow; neither
+// is ever a StakeError custom code.
 pub(crate) fn checked_add(a: u64, b: u64) -> Result<u64, ProgramError> {
     a.checked_add(b).ok_or(ProgramError::InsufficientFunds)
 }
@@ -517,3 +697,261 @@ pub fn collect_signers_checked<'a>(
 }
 
    
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::test_utils::{
+        account_info, raw_account, raw_stake_account, stake_account_info, RawStakeAccount,
+    };
+    use crate::state::StakeFlags;
+
+    #[test]
+    fn next_account_wrappers_failure_modes() {
+        let mut readonly = raw_account(false, false, ID);
+        let mut signer = raw_account(true, true, [9u8; 32]);
+        let accounts = [account_info(&mut readonly), account_info(&mut signer)];
+
+        assert_eq!(
+            next_writable_account(&mut accounts[..1].iter()).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            next_signer_account(&mut accounts[..1].iter()).err(),
+            Some(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            next_owned_account(&mut accounts[1..].iter(), &ID).err(),
+            Some(ProgramError::InvalidAccountOwner)
+        );
+        for empty in [
+            next_writable_account(&mut accounts[..0].iter()).err(),
+            next_signer_account(&mut accounts[..0].iter()).err(),
+            next_owned_account(&mut accounts[..0].iter(), &ID).err(),
+        ] {
+            assert_eq!(empty, Some(ProgramError::NotEnoughAccountKeys));
+        }
+
+        // Each wrapper advances the iterator by exactly one account
+        let iter = &mut accounts.iter();
+        assert!(next_owned_account(iter, &ID).is_ok());
+        assert!(next_signer_account(iter).is_ok());
+        assert!(iter.next().is_none());
+        assert!(next_writable_account(&mut accounts[1..].iter()).is_ok());
+    }
+
+    #[test]
+    fn assert_stake_sized_requires_exact_length() {
+        let mut raw = RawStakeAccount { header: raw_account(false, true, ID), data: [0u8; StakeStateV2::ACCOUNT_SIZE] };
+        for (len, expected) in [
+            (StakeStateV2::ACCOUNT_SIZE - 1, Err(ProgramError::InvalidAccountData)),
+            (StakeStateV2::ACCOUNT_SIZE, Ok(())),
+            (StakeStateV2::ACCOUNT_SIZE + 1, Err(ProgramError::InvalidAccountData)),
+        ] {
+            raw.header.data_len = len as u64;
+            // Re-derive the view after each write to the header
+            let ai = stake_account_info(&mut raw);
+            assert_eq!(assert_stake_sized(&ai), expected, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn assert_owned_by_program_checks_owner() {
+        let mut owned = raw_account(false, false, ID);
+        assert_eq!(assert_owned_by_program(&account_info(&mut owned)), Ok(()));
+
+        let mut foreign = raw_account(false, true, [1u8; 32]);
+        assert_eq!(
+            assert_owned_by_program(&account_info(&mut foreign)),
+            Err(ProgramError::InvalidAccountOwner)
+        );
+    }
+
+    #[test]
+    fn require_signer_matches_key_and_signature() {
+        let mut signed = raw_account(true, false, ID);
+        let mut unsigned = raw_account(false, false, ID);
+        unsigned.key = [8u8; 32];
+        let accounts = [account_info(&mut unsigned), account_info(&mut signed)];
+
+        assert_eq!(require_signer(&accounts, &[7u8; 32]), Ok(()));
+        // Present but not a signer
+        assert_eq!(require_signer(&accounts, &[8u8; 32]), Err(ProgramError::MissingRequiredSignature));
+        // Absent
+        assert_eq!(require_signer(&accounts, &[1u8; 32]), Err(ProgramError::MissingRequiredSignature));
+        assert_eq!(require_signer(&[], &[7u8; 32]), Err(ProgramError::MissingRequiredSignature));
+    }
+
+    #[test]
+    fn find_custodian_signer_skips_unsigned_matches() {
+        let mut unsigned = raw_account(false, false, ID);
+        let mut other = raw_account(true, false, ID);
+        other.key = [8u8; 32];
+        let mut signed = raw_account(true, false, ID);
+        signed.lamports = 1;
+        let accounts = [account_info(&mut unsigned), account_info(&mut other), account_info(&mut signed)];
+
+        // The unsigned meta with the same key is passed over for the signed one
+        let found = find_custodian_signer(&accounts, &[7u8; 32]).unwrap();
+        assert!(found.is_signer());
+        assert_eq!(found.lamports(), 1);
+        assert!(find_custodian_signer(&accounts[..2], &[7u8; 32]).is_none());
+        assert!(find_custodian_signer(&accounts, &[1u8; 32]).is_none());
+        assert!(find_custodian_signer(&[], &[7u8; 32]).is_none());
+    }
+
+    #[test]
+    fn set_stake_state_rejects_program_id_account() {
+        let mut raw = raw_account(false, true, ID);
+        raw.key = ID;
+        let ai = account_info(&mut raw);
+        assert_eq!(
+            set_stake_state(&ai, &StakeStateV2::Uninitialized),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn get_stake_state_reads_borrowed_data() {
+        let mut meta = Meta::default();
+        meta.rent_exempt_reserve = 42u64.to_le_bytes();
+        let state = StakeStateV2::Initialized(meta);
+
+        let mut raw = raw_stake_account([7u8; 32], 0, &state);
+        let ai = stake_account_info(&mut raw);
+
+        // Decode straight from the Ref; a shared borrow coexists with get_stake_state
+        let data = ai.try_borrow_data().unwrap();
+        assert_eq!(StakeStateV2::deserialize(&data).unwrap(), state);
+        assert_eq!(get_stake_state(&ai).unwrap(), state);
+        drop(data);
+
+        // A live mutable borrow is reported, not read through
+        let guard = ai.try_borrow_mut_data().unwrap();
+        assert_eq!(get_stake_state(&ai), Err(ProgramError::AccountBorrowFailed));
+        drop(guard);
+        assert_eq!(get_stake_state(&ai).unwrap(), state);
+    }
+
+    #[test]
+    fn in_place_deactivate_touches_only_deactivation_epoch() {
+        let mut stake = Stake::default();
+        stake.delegation.voter_pubkey = [3u8; 32];
+        stake.delegation.stake = 1_000u64.to_le_bytes();
+        stake.delegation.activation_epoch = 4u64.to_le_bytes();
+        stake.delegation.deactivation_epoch = u64::MAX.to_le_bytes();
+        stake.credits_observed = 55u64.to_le_bytes();
+        let mut meta = Meta::default();
+        meta.rent_exempt_reserve = 42u64.to_le_bytes();
+        let state = StakeStateV2::Stake(meta, stake, StakeFlags::empty());
+
+        let mut raw = raw_stake_account([7u8; 32], 0, &state);
+        let before = raw.data;
+        let ai = stake_account_info(&mut raw);
+
+        let mut handle = get_stake_state_mut(&ai).unwrap();
+        assert_eq!(handle.state().unwrap(), state);
+        // The handle holds the mutable borrow
+        assert_eq!(get_stake_state(&ai), Err(ProgramError::AccountBorrowFailed));
+        handle.set_deactivation_epoch(9).unwrap();
+        drop(handle);
+
+        // Same bytes as a full re-serialization of the deactivated state
+        stake.deactivate(9).unwrap();
+        let mut expected = [0u8; StakeStateV2::ACCOUNT_SIZE];
+        StakeStateV2::Stake(meta, stake, StakeFlags::empty()).serialize(&mut expected).unwrap();
+        assert_eq!(raw.data, expected);
+
+        let off = 1 + 120 + 32 + 8 + 8;
+        for (i, (a, b)) in before.iter().zip(raw.data.iter()).enumerate() {
+            if !(off..off + 8).contains(&i) {
+                assert_eq!(a, b, "byte {} changed", i);
+            }
+        }
+
+        // Non-Stake states are refused
+        StakeStateV2::Initialized(meta).serialize(&mut raw.data).unwrap();
+        let ai = stake_account_info(&mut raw);
+        assert_eq!(
+            get_stake_state_mut(&ai).unwrap().set_deactivation_epoch(9),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "writing a Stake with zero delegation")]
+    fn set_stake_state_asserts_on_zero_stake() {
+        let state = StakeStateV2::Stake(Meta::default(), Stake::default(), StakeFlags::empty());
+        let mut raw = raw_stake_account([7u8; 32], 0, &StakeStateV2::Uninitialized);
+        let _ = set_stake_state(&stake_account_info(&mut raw), &state);
+    }
+
+    struct NoHistory;
+    impl StakeHistoryGetEntry for NoHistory {
+        fn get_entry(&self, _epoch: u64) -> Option<crate::state::stake_history::StakeHistoryEntry> {
+            None
+        }
+    }
+
+    fn clock_at(epoch: u64) -> Clock {
+        Clock { slot: 0, epoch_start_timestamp: 0, epoch, leader_schedule_epoch: epoch, unix_timestamp: 0 }
+    }
+
+    #[test]
+    fn free_lamports_per_state() {
+        let mut meta = Meta::default();
+        meta.rent_exempt_reserve = 100u64.to_le_bytes();
+        let mut stake = Stake::default();
+        stake.delegation.stake = 1_000u64.to_le_bytes();
+        stake.delegation.activation_epoch = 0u64.to_le_bytes();
+
+        // Initialized: everything above the reserve
+        let initialized = StakeStateV2::Initialized(meta);
+        assert_eq!(free_lamports(&initialized, 350, &clock_at(10), &NoHistory), 250);
+        assert_eq!(free_lamports(&initialized, 50, &clock_at(10), &NoHistory), 0);
+
+        // Active: the delegation stays put
+        let active = StakeStateV2::Stake(meta, stake, StakeFlags::empty());
+        assert_eq!(free_lamports(&active, 1_350, &clock_at(10), &NoHistory), 250);
+
+        // Deactivation scheduled but not reached: still fully staked
+        stake.delegation.deactivation_epoch = 12u64.to_le_bytes();
+        let deactivating = StakeStateV2::Stake(meta, stake, StakeFlags::empty());
+        assert_eq!(free_lamports(&deactivating, 1_350, &clock_at(10), &NoHistory), 250);
+
+        // Cooled down (no history: deactivation is immediate): stake is free again
+        assert_eq!(free_lamports(&deactivating, 1_350, &clock_at(13), &NoHistory), 1_250);
+
+        assert_eq!(free_lamports(&StakeStateV2::Uninitialized, 1_350, &clock_at(13), &NoHistory), 0);
+    }
+
+    #[test]
+    fn minimum_delegation_boundary() {
+        let min = get_minimum_delegation();
+        assert_eq!(enforce_minimum_delegation(min), Ok(()));
+        assert_eq!(enforce_minimum_delegation(min + 1), Ok(()));
+        assert_eq!(
+            enforce_minimum_delegation(min - 1),
+            Err(to_program_error(StakeError::InsufficientDelegation))
+        );
+    }
+
+    #[test]
+    fn relocate_overflow_leaves_source_unchanged() {
+        let source = 1_000u64;
+        let destination = u64::MAX - 10;
+
+        // Credit overflows: nothing is computed for either side
+        assert_eq!(
+            relocated_balances(source, destination, 100),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+        // Debit underflow is reported before the credit is attempted
+        assert_eq!(
+            relocated_balances(source, destination, 1_001),
+            Err(ProgramError::InsufficientFunds)
+        );
+        assert_eq!(relocated_balances(source, destination, 10), Ok((990, u64::MAX)));
+    }
+}
@@ -4,7 +4,7 @@ use pinocchio::{
 };
 
 use crate::{
-    helpers::{get_stake_state, set_stake_state},
+    helpers::{find_custodian_signer, get_stake_state, set_stake_state},
     state::{stake_state_v2::StakeStateV2, StakeAuthorize},
 };
 use crate::helpers::authorize_update;
@@ -53,9 +53,7 @@ pub fn process_authorize(
         StakeStateV2::Stake(meta, _, _) => meta.lockup.custodian,
         _ => return Err(ProgramError::InvalidAccountData),
     };
-    let maybe_lockup_authority: Option<&AccountInfo> = rest
-        .iter()
-        .find(|ai| ai.is_signer() && ai.key() == &custodian_pk);
+    let maybe_lockup_authority = find_custodian_signer(rest, &custodian_pk);
 
     // Restricted signers slice: current authority and optional custodian
     let mut signers = [Pubkey::default(); 2];
@@ -8,7 +8,7 @@ use pinocchio::{
 
 use crate::{
     error::to_program_error,
-    helpers::{collect_signers, get_stake_state, set_stake_state, MAXIMUM_SIGNERS},
+    helpers::{collect_signers, get_stake_state_mut, MAXIMUM_SIGNERS},
     state::{stake_state_v2::StakeStateV2, StakeAuthorize},
 };
 
@@ -26,16 +26,19 @@ pub fn process_deactivate(accounts: &[AccountInfo]) -> ProgramResult {
     if *stake_ai.owner() != crate::ID { return Err(ProgramError::InvalidAccountOwner); }
     if !stake_ai.is_writable() { return Err(ProgramError::InvalidInstructionData); }
 
+    // Clock comes from the sysvar syscall; the clock meta (if passed) is not compared by key.
     let clock = Clock::get()?;
-    match get_stake_state(stake_ai)? {
-        StakeStateV2::Stake(meta, mut stake, flags) => {
+    // Only the deactivation epoch changes, so write it in place
+    let mut stake_state = get_stake_state_mut(stake_ai)?;
+    match stake_state.state()? {
+        StakeStateV2::Stake(meta, mut stake, _flags) => {
             // Enforce staker signature (maps to MissingRequiredSignature on failure)
             meta.authorized
                 .check(signers, StakeAuthorize::Staker)
                 .map_err(to_program_error)?;
 
-            stake.deactivate(clock.epoch.to_le_bytes()).map_err(to_program_error)?;
-            set_stake_state(stake_ai, &StakeStateV2::Stake(meta, stake, flags))?;
+            stake.deactivate(clock.epoch).map_err(to_program_error)?;
+            stake_state.set_deactivation_epoch(clock.epoch)?;
             Ok(())
         }
         _ => Err(ProgramError::InvalidAccountData),
@@ -49,13 +49,7 @@ pub fn process_deactivate_delinquent(accounts: &[AccountInfo]) -> ProgramResult
         // delinquent_ok
         let del_ok = {
             let data = del_ai.try_borrow_data()?;
-            if data.len() < 4 { true } else { match last_vote_epoch_bytes(&data)? {
-                None => true,
-                Some(last) => match clock.epoch.checked_sub(n) {
-                    Some(min_epoch) => last <= min_epoch,
-                    None => false,
-                }
-            } }
+            data.len() < 4 || eligible_for_deactivate_delinquent(last_vote_epoch_bytes(&data)?, clock.epoch, n)
         };
         Ok((ref_ok, del_ok))
     };
@@ -80,12 +74,8 @@ pub fn process_deactivate_delinquent(accounts: &[AccountInfo]) -> ProgramResult
                 if found_del.is_none() {
                     if bytes.len() < 4 {
                         found_del = Some(ai);
-                    } else if let Ok(Some(last)) = last_vote_epoch_bytes(&bytes) {
-                        if let Some(min_epoch) = clock.epoch.checked_sub(n) {
-                            if last <= min_epoch { found_del = Some(ai); }
-                        }
-                    } else if let Ok(None) = last_vote_epoch_bytes(&bytes) {
-                        found_del = Some(ai);
+                    } else if let Ok(last) = last_vote_epoch_bytes(&bytes) {
+                        if eligible_for_deactivate_delinquent(last, clock.epoch, n) { found_del = Some(ai); }
                     }
                 }
                 if let (Some(rf), Some(dl)) = (found_ref, found_del) {
@@ -120,7 +110,7 @@ pub fn process_deactivate_delinquent(accounts: &[AccountInfo]) -> ProgramResult
                 return Err(to_program_error(StakeError::VoteAddressMismatch));
             }
             // Set deactivation_epoch = current epoch (Epoch is [u8;8])
-            stake.deactivate(clock.epoch.to_le_bytes()).map_err(to_program_error)?;
+            stake.deactivate(clock.epoch).map_err(to_program_error)?;
             set_stake_state(stake_ai, &StakeStateV2::Stake(meta, stake, flags))
         }
         _ => Err(ProgramError::InvalidAccountData),
@@ -172,6 +162,20 @@ fn acceptable_reference_epoch_credits_bytes(
     Ok(prev)
 }
 
+/// A vote account that never voted is delinquent; otherwise its last vote must be
+/// at least `n` epochs old. While the cluster is younger than `n` epochs
+/// (`current_epoch < n`) nothing can be delinquent yet, which surfaces as
+/// `MinimumDelinquentEpochsForDeactivationNotMet`.
+fn eligible_for_deactivate_delinquent(last_vote_epoch: Option<u64>, current_epoch: u64, n: u64) -> bool {
+    match last_vote_epoch {
+        None => true,
+        Some(last) => match current_epoch.checked_sub(n) {
+            Some(min_epoch) => last <= min_epoch,
+            None => false,
+        },
+    }
+}
+
 fn last_vote_epoch_bytes(data: &[u8]) -> Result<Option<u64>, ProgramError> {
     if data.len() < 4 {
         return Err(ProgramError::InvalidAccountData);
@@ -279,4 +283,71 @@ fn not_delinquent_if_last_vote_within_n() {
     let min_epoch = current - 5;
     assert!(!(last.unwrap() <= min_epoch));
 }
+
+#[test]
+fn minimum_delinquent_epochs_matches_native() {
+    assert_eq!(MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION, 5);
+    assert_eq!(
+        MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION as usize,
+        solana_stake_interface::MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION
+    );
+}
+
+// Native `acceptable_reference_epoch_credits`: the last N entries are exactly
+// current, current - 1, ..., current - (N - 1)
+fn native_acceptable_reference(epoch_credits: &[(u64, u64, u64)], current_epoch: u64) -> bool {
+    let n = solana_stake_interface::MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION;
+    let Some(start) = epoch_credits.len().checked_sub(n) else { return false };
+    let mut epoch = current_epoch;
+    for (vote_epoch, ..) in epoch_credits[start..].iter().rev() {
+        if *vote_epoch != epoch {
+            return false;
+        }
+        epoch = epoch.saturating_sub(1);
+    }
+    true
+}
+
+#[test]
+fn reference_window_matches_native_for_n5() {
+    use alloc::vec::Vec;
+    let n = MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION;
+    for current in [5u64, 6, 40, 1_000] {
+        // (current - (n - 1))..=current, each epoch with a positive vote
+        let window: Vec<(u64, u64, u64)> = (current + 1 - n..=current).map(|e| (e, e + 1, e)).collect();
+        let mut older_first = alloc::vec![(current - n, 1, 0)];
+        older_first.extend_from_slice(&window);
+        let mut gap = older_first.clone();
+        gap.remove(3);
+        let ahead: Vec<_> = window.iter().map(|&(e, c, p)| (e + 1, c, p)).collect();
+        // A window ending at current - 1 is left out: it is accepted here (see
+        // `reference_window_previous_epoch_ok`) but not by native
+        for (name, list) in [
+            ("window", window.clone()),
+            ("one short", window[1..].to_vec()),
+            ("older history first", older_first),
+            ("gap", gap),
+            ("ahead of current", ahead),
+        ] {
+            let bytes = build_epoch_credits_bytes(&list);
+            assert_eq!(
+                acceptable_reference_epoch_credits_bytes(&bytes, current, n).unwrap(),
+                native_acceptable_reference(&list, current),
+                "{} at epoch {}",
+                name,
+                current
+            );
+        }
+    }
+}
+
+#[test]
+fn young_cluster_is_never_delinquent() {
+    // current=3 < N=5 => no min_epoch exists, even for a vote at epoch 0
+    assert!(!eligible_for_deactivate_delinquent(Some(0), 3, 5));
+    assert!(!eligible_for_deactivate_delinquent(Some(0), 4, 5));
+    assert!(eligible_for_deactivate_delinquent(Some(0), 5, 5));
+    // never voted stays eligible regardless of cluster age
+    assert!(eligible_for_deactivate_delinquent(None, 3, 5));
+}
 }
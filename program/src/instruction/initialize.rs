@@ -63,11 +63,7 @@ pub fn do_initialize(
         let rent_exempt_reserve = rent.minimum_balance(stake_account_info.data_len());
         cu("do_initialize: after rent calc");
         if stake_account_info.lamports() >= rent_exempt_reserve {
-            let stake_state = StakeStateV2::Initialized(Meta {
-                rent_exempt_reserve: rent_exempt_reserve.to_le_bytes(),
-                authorized,
-                lockup,
-            });
+            let stake_state = StakeStateV2::Initialized(Meta::new(rent_exempt_reserve, authorized, lockup));
 
             cu("do_initialize: before write");
             let res = set_stake_state(stake_account_info, &stake_state);
@@ -43,10 +43,7 @@ pub fn process_initialize_checked(accounts: &[AccountInfo]) -> ProgramResult {
         }
         cu("init_checked: signer ok");
 
-        let authorized = Authorized {
-            staker: *stake_authority_info.key(),
-            withdrawer: *withdraw_authority_info.key(),
-        };
+        let authorized = Authorized::new(*stake_authority_info.key(), *withdraw_authority_info.key());
 
         // `get_stake_state()` is called unconditionally, which checks owner
         cu("init_checked: before do_initialize");
@@ -7,18 +7,18 @@ extern crate alloc;
 //   sysvars present, staker authorization, and metadata (authorities/lockups) compatibility.
 // - Classification uses `MergeKind::get_if_mergeable(..)` and supports the common shape pairs:
 //   IN+IN, IN+AE, AE+IN, AE+AE, FA+FA. On success, source is drained and uninitialized.
-// - StakeHistory caveat: we intentionally do not read the full stake_history contents. Instead
-//   we wrap the current epoch in `StakeHistorySysvar(clock.epoch)` and rely on classification
-//   fallbacks (e.g., clearly deactivated shapes → Inactive). This is faithful for mainstream
-//   cases, but may diverge from native at epoch boundaries where effective/partial activation
-//   or cooldown depend on the actual StakeHistory entries.
-//   If strict parity at boundaries is required, consider adding a feature flag that reads a
-//   minimal slice of the sysvar (e.g., `get_entry(current_epoch-1)`) to disambiguate partial
-//   activation/cooldown before classification.
+// - StakeHistory caveat: by default classification leans on epoch fast paths and
+//   fallbacks (e.g., clearly deactivated shapes → Inactive, past activation → FullyActive).
+//   This is faithful for mainstream cases, but may diverge from native at epoch boundaries
+//   where partial activation or cooldown depend on the actual StakeHistory entries.
+//   With the `strict-history` feature, classification reads the StakeHistory sysvar entries
+//   (`StakeHistorySysvar(clock.epoch)`) and applies the native table with no fallbacks, so a
+//   stake still warming up or cooling down is rejected as transient.
 
 use crate::{
     error::{to_program_error, StakeError},
     helpers::{
+        assert_owned_by_program,
         collect_signers,
         constant::MAXIMUM_SIGNERS,
         checked_add,
@@ -26,9 +26,9 @@ use crate::{
         get_stake_state,
         relocate_lamports,
         set_stake_state,
+        utils::relocated_balances,
     },
-    state::{stake_state_v2::StakeStateV2, MergeKind, StakeHistorySysvar},
-    ID,
+    state::{stake_history::StakeHistoryGetEntry, stake_state_v2::StakeStateV2, MergeKind, StakeHistorySysvar},
 };
 
 use pinocchio::{
@@ -47,14 +47,13 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
     if dst_ai.key() == src_ai.key() { return Err(ProgramError::InvalidArgument); }
-    if *dst_ai.owner() != ID || *src_ai.owner() != ID { return Err(ProgramError::InvalidAccountOwner); }
+    assert_owned_by_program(dst_ai)?;
+    assert_owned_by_program(src_ai)?;
     if !dst_ai.is_writable() || !src_ai.is_writable() { return Err(ProgramError::InvalidInstructionData); }
     // clock will be validated by Clock::from_account_info
     if stake_history_ai.key() != &crate::state::stake_history::ID { return Err(ProgramError::InvalidInstructionData); }
 
     let clock = Clock::from_account_info(clock_ai)?;
-    // Use the epoch wrapper; contents of stake_history account are not read here
-    let stake_history = StakeHistorySysvar(clock.epoch);
 
     // Enforce exact data size parity with native handlers
     if dst_ai.data_len() != StakeStateV2::size_of() || src_ai.data_len() != StakeStateV2::size_of() {
@@ -66,6 +65,62 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
     let n = collect_signers(accounts, &mut signer_buf)?;
     let signers = &signer_buf[..n];
 
+    // Entries are read through the sysvar syscall; see `classify` for how they are used
+    merge_checked_pair(dst_ai, src_ai, &clock, &StakeHistorySysvar(clock.epoch), signers)
+}
+
+/// Merge classification with the epoch fallbacks: a classification error on an
+/// Initialized account or a stake past its deactivation epoch still yields Inactive.
+#[cfg(not(feature = "strict-history"))]
+fn classify<T: StakeHistoryGetEntry>(
+    state: &StakeStateV2,
+    lamports: u64,
+    clock: &Clock,
+    stake_history: &T,
+) -> Result<MergeKind, ProgramError> {
+    match MergeKind::get_if_mergeable(state, lamports, clock, stake_history) {
+        Ok(k) => Ok(k),
+        Err(_) => match state {
+            StakeStateV2::Initialized(meta) => Ok(MergeKind::Inactive(*meta, lamports, crate::state::stake_flag::StakeFlags::empty())),
+            StakeStateV2::Stake(meta, stake, flags) => {
+                let deact = crate::helpers::bytes_to_u64(stake.delegation.deactivation_epoch);
+                if deact != u64::MAX && clock.epoch > deact {
+                    Ok(MergeKind::Inactive(*meta, lamports, *flags))
+                } else {
+                    Err(to_program_error(StakeError::MergeMismatch))
+                }
+            }
+            _ => Err(to_program_error(StakeError::MergeMismatch)),
+        },
+    }
+}
+
+/// Merge classification from the StakeHistory entries alone, as native does.
+#[cfg(feature = "strict-history")]
+fn classify<T: StakeHistoryGetEntry>(
+    state: &StakeStateV2,
+    lamports: u64,
+    clock: &Clock,
+    stake_history: &T,
+) -> Result<MergeKind, ProgramError> {
+    MergeKind::get_if_mergeable_from_history(state, lamports, clock, stake_history)
+}
+
+/// Classify both accounts against `stake_history` and merge `src_ai` into
+/// `dst_ai` once account roles, sizes and sysvars have been validated by the
+/// caller. Requires the destination staker among `signers`.
+///
+/// Every arm writes the destination and source states first and drains the
+/// source lamports last. The drain is checked before any write, so once a
+/// state has been written the relocation cannot fail and an error leaves
+/// both accounts as they were.
+pub(crate) fn merge_checked_pair<T: StakeHistoryGetEntry>(
+    dst_ai: &AccountInfo,
+    src_ai: &AccountInfo,
+    clock: &Clock,
+    stake_history: &T,
+    signers: &[Pubkey],
+) -> ProgramResult {
     // Classify destination & require staker auth
     let dst_state = get_stake_state(dst_ai)?;
     match &dst_state {
@@ -74,29 +129,7 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
         StakeStateV2::Uninitialized => pinocchio::msg!("merge:dst_state=Uninit"),
         _ => pinocchio::msg!("merge:dst_state=Other"),
     }
-    let dst_kind = match MergeKind::get_if_mergeable(
-        &dst_state,
-        dst_ai.lamports(),
-        &clock,
-        &stake_history,
-    ) {
-        Ok(k) => k,
-        Err(_) => {
-            // Fallback: treat clearly inactive shapes as Inactive for merge classification
-            match &dst_state {
-                StakeStateV2::Initialized(meta) => MergeKind::Inactive(*meta, dst_ai.lamports(), crate::state::stake_flag::StakeFlags::empty()),
-                StakeStateV2::Stake(meta, stake, flags) => {
-                    let deact = crate::helpers::bytes_to_u64(stake.delegation.deactivation_epoch);
-                    if deact != u64::MAX && clock.epoch > deact {
-                        MergeKind::Inactive(*meta, dst_ai.lamports(), *flags)
-                    } else {
-                        return Err(to_program_error(StakeError::MergeMismatch));
-                    }
-                }
-                _ => return Err(to_program_error(StakeError::MergeMismatch)),
-            }
-        }
-    };
+    let dst_kind = classify(&dst_state, dst_ai.lamports(), clock, stake_history)?;
     match &dst_kind {
         MergeKind::FullyActive(_, _) => pinocchio::msg!("merge:dst=FA"),
         MergeKind::Inactive(_, _, _) => pinocchio::msg!("merge:dst=IN"),
@@ -125,28 +158,7 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
     // and the unconditional source deinitialize + lamport drain below when
     // MergeKind::merge returns None, preserving native semantics without extra
     // branches.
-    let src_kind = match MergeKind::get_if_mergeable(
-        &src_state,
-        src_ai.lamports(),
-        &clock,
-        &stake_history,
-    ) {
-        Ok(k) => k,
-        Err(_) => {
-            match &src_state {
-                StakeStateV2::Initialized(meta) => MergeKind::Inactive(*meta, src_ai.lamports(), crate::state::stake_flag::StakeFlags::empty()),
-                StakeStateV2::Stake(meta, stake, flags) => {
-                    let deact = crate::helpers::bytes_to_u64(stake.delegation.deactivation_epoch);
-                    if deact != u64::MAX && clock.epoch > deact {
-                        MergeKind::Inactive(*meta, src_ai.lamports(), *flags)
-                    } else {
-                        return Err(to_program_error(StakeError::MergeMismatch));
-                    }
-                }
-                _ => return Err(to_program_error(StakeError::MergeMismatch)),
-            }
-        }
-    };
+    let src_kind = classify(&src_state, src_ai.lamports(), clock, stake_history)?;
     match &src_kind {
         MergeKind::FullyActive(_, _) => pinocchio::msg!("merge:src=FA"),
         MergeKind::Inactive(_, _, _) => pinocchio::msg!("merge:src=IN"),
@@ -154,8 +166,12 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
     }
 
     // Ensure metadata compatibility (authorities equal, lockups compatible)
-    MergeKind::metas_can_merge(dst_kind.meta(), src_kind.meta(), &clock)?;
+    MergeKind::metas_can_merge(dst_kind.meta(), src_kind.meta(), clock)?;
     pinocchio::msg!("merge:metas_ok");
+
+    // All arms end by draining the source into the destination; fail here,
+    // before any state write, if that relocation cannot succeed
+    relocated_balances(src_ai.lamports(), dst_ai.lamports(), src_ai.lamports())?;
     pinocchio::msg!("merge:after_metas");
 
     // Fast-path already attempted using raw states above
@@ -179,7 +195,7 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
             let mut stake_out = src_stake;
             stake_out.delegation.stake = new_stake.to_le_bytes();
             let merged_flags = dst_flags.union(src_flags);
-            set_stake_state(dst_ai, &StakeStateV2::Stake(dst_meta, stake_out, merged_flags))?;
+            set_stake_state(dst_ai, &StakeStateV2::new_stake(dst_meta, stake_out, merged_flags)?)?;
             set_stake_state(src_ai, &StakeStateV2::Uninitialized)?;
             relocate_lamports(src_ai, dst_ai, src_ai.lamports())?;
             return Ok(());
@@ -189,7 +205,7 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
             let new_stake = checked_add(bytes_to_u64(stake.delegation.stake), src_lamports)?;
             stake.delegation.stake = new_stake.to_le_bytes();
             let merged_flags = dst_flags.union(src_flags);
-            set_stake_state(dst_ai, &StakeStateV2::Stake(meta, stake, merged_flags))?;
+            set_stake_state(dst_ai, &StakeStateV2::new_stake(meta, stake, merged_flags)?)?;
             set_stake_state(src_ai, &StakeStateV2::Uninitialized)?;
             relocate_lamports(src_ai, dst_ai, src_ai.lamports())?;
             return Ok(());
@@ -199,7 +215,7 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
             let src_stake_lamports = checked_add(bytes_to_u64(src_meta.rent_exempt_reserve), bytes_to_u64(src_stake.delegation.stake))?;
             crate::helpers::merge::merge_delegation_stake_and_credits_observed(&mut dst_stake, src_stake_lamports, bytes_to_u64(src_stake.credits_observed))?;
             let merged_flags = dst_flags.union(src_flags);
-            set_stake_state(dst_ai, &StakeStateV2::Stake(dst_meta, dst_stake, merged_flags))?;
+            set_stake_state(dst_ai, &StakeStateV2::new_stake(dst_meta, dst_stake, merged_flags)?)?;
             set_stake_state(src_ai, &StakeStateV2::Uninitialized)?;
             relocate_lamports(src_ai, dst_ai, src_ai.lamports())?;
             return Ok(());
@@ -207,7 +223,7 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
         (MergeKind::FullyActive(dst_meta, mut dst_stake), MergeKind::FullyActive(_, src_stake)) => {
             pinocchio::msg!("merge:inline FA+FA");
             crate::helpers::merge::merge_delegation_stake_and_credits_observed(&mut dst_stake, bytes_to_u64(src_stake.delegation.stake), bytes_to_u64(src_stake.credits_observed))?;
-            set_stake_state(dst_ai, &StakeStateV2::Stake(dst_meta, dst_stake, crate::state::stake_flag::StakeFlags::empty()))?;
+            set_stake_state(dst_ai, &StakeStateV2::new_stake(dst_meta, dst_stake, crate::state::stake_flag::StakeFlags::empty())?)?;
             set_stake_state(src_ai, &StakeStateV2::Uninitialized)?;
             relocate_lamports(src_ai, dst_ai, src_ai.lamports())?;
             return Ok(());
@@ -218,3 +234,38 @@ pub fn process_merge(accounts: &[AccountInfo]) -> ProgramResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::test_utils::{raw_stake_account, stake_account_info};
+    use crate::state::{stake_history::StakeHistoryEntry, Meta};
+
+    #[test]
+    fn failed_drain_leaves_both_accounts_unwritten() {
+        let mut meta = Meta::default();
+        meta.rent_exempt_reserve = 10u64.to_le_bytes();
+        meta.authorized.staker = [3u8; 32];
+        meta.authorized.withdrawer = [4u8; 32];
+        let state = StakeStateV2::Initialized(meta);
+
+        // Inactive + inactive, with a destination balance the source drain overflows
+        let mut dst = raw_stake_account([7u8; 32], u64::MAX, &state);
+        let mut src = raw_stake_account([8u8; 32], 100, &state);
+        let dst_before = dst.data;
+        let src_before = src.data;
+        let (dst_ai, src_ai) = (stake_account_info(&mut dst), stake_account_info(&mut src));
+
+        let clock = Clock { slot: 0, epoch_start_timestamp: 0, epoch: 5, leader_schedule_epoch: 5, unix_timestamp: 0 };
+        let history: &[(u64, StakeHistoryEntry)] = &[];
+        assert_eq!(
+            merge_checked_pair(&dst_ai, &src_ai, &clock, &history, &[[3u8; 32]]),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+
+        assert_eq!(get_stake_state(&src_ai).unwrap(), state);
+        assert_eq!((dst_ai.lamports(), src_ai.lamports()), (u64::MAX, 100));
+        assert_eq!(dst.data, dst_before);
+        assert_eq!(src.data, src_before);
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "merge-many")]
+// MergeMany (non-native, feature `merge-many`): merge several sources into one
+// destination in a single instruction.
+//
+// Wire: the single byte `MERGE_MANY_TAG`, no payload.
+// Accounts: [destination, clock, stake_history, source_1, .., source_n, staker]
+// Each source is merged in order with the same rules as `Merge`; the first
+// failure aborts the instruction (so no source is merged) and logs its position
+// among the sources (0-based).
+
+use crate::{
+    helpers::{collect_signers, constant::MAXIMUM_SIGNERS},
+    instruction::merge_dedicated::merge_checked_pair,
+    state::{stake_history::StakeHistoryGetEntry, stake_state_v2::StakeStateV2, StakeHistorySysvar},
+    ID,
+};
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::clock::Clock,
+    ProgramResult,
+};
+use pinocchio_log::log;
+
+/// Instruction tag; well above the native range so it never shadows a native variant.
+pub const MERGE_MANY_TAG: u8 = 240;
+
+pub fn process_merge_many(accounts: &[AccountInfo]) -> ProgramResult {
+    let [dst_ai, clock_ai, stake_history_ai, sources @ .., _staker_ai] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if sources.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    if *dst_ai.owner() != ID { return Err(ProgramError::InvalidAccountOwner); }
+    if !dst_ai.is_writable() { return Err(ProgramError::InvalidInstructionData); }
+    if stake_history_ai.key() != &crate::state::stake_history::ID { return Err(ProgramError::InvalidInstructionData); }
+    let clock = Clock::from_account_info(clock_ai)?;
+    if dst_ai.data_len() != StakeStateV2::size_of() { return Err(ProgramError::InvalidAccountData); }
+
+    let mut signer_buf = [Pubkey::default(); MAXIMUM_SIGNERS];
+    let n = collect_signers(accounts, &mut signer_buf)?;
+    let signers = &signer_buf[..n];
+
+    let stake_history = StakeHistorySysvar(clock.epoch);
+    for (index, src_ai) in sources.iter().enumerate() {
+        merge_source(dst_ai, src_ai, &clock, &stake_history, signers).inspect_err(|_| {
+            log!("merge_many:source {} failed", index as u64);
+        })?;
+    }
+    Ok(())
+}
+
+// Per-source account checks from `process_merge`, then the shared merge
+fn merge_source<T: StakeHistoryGetEntry>(
+    dst_ai: &AccountInfo,
+    src_ai: &AccountInfo,
+    clock: &Clock,
+    stake_history: &T,
+    signers: &[Pubkey],
+) -> ProgramResult {
+    if dst_ai.key() == src_ai.key() { return Err(ProgramError::InvalidArgument); }
+    if *src_ai.owner() != ID { return Err(ProgramError::InvalidAccountOwner); }
+    if !src_ai.is_writable() { return Err(ProgramError::InvalidInstructionData); }
+    if src_ai.data_len() != StakeStateV2::size_of() { return Err(ProgramError::InvalidAccountData); }
+    merge_checked_pair(dst_ai, src_ai, clock, stake_history, signers)
+}
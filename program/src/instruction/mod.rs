@@ -1,55 +1,49 @@
 use pinocchio::program_error::ProgramError;
 
+// Handler modules. Each handler is also re-exported flat below so embedding
+// programs can call e.g. `instruction::process_delegate(accounts)`.
+// The flat list replaced `pub use <module>::*`; keep it in step with every
+// `pub` item of the modules so no previously exported name goes missing.
 pub mod initialize;
-pub use initialize::*;
-
 pub mod initialize_checked;
-pub use initialize_checked::*;
-
 pub mod split;
-pub use split::*;
-
 pub mod process_set_lockup;
-pub use process_set_lockup::*;
-
 pub mod authorize;
-pub use authorize::*;
-
 pub mod authorize_checked;
-pub use authorize_checked::*;
-
 pub mod process_authorized_with_seeds;
-pub use process_authorized_with_seeds::*;
-
 pub mod process_authorize_checked_with_seed;
-pub use process_authorize_checked_with_seed::*;
-
 pub mod merge;
 pub mod merge_dedicated;
-pub use merge::*;
-
+pub mod merge_many;
 pub mod process_delegate;
-pub use process_delegate::*;
-
 pub mod process_move_stake;
-pub use process_move_stake::*;
-
 pub mod process_redelegate;
-pub use process_redelegate::*;
-
 pub mod deactivate_delinquent;
-pub use deactivate_delinquent::*;
 pub mod move_lamports;
-pub use move_lamports::*;
-
 pub mod withdraw;
-pub use withdraw::*;
-
 pub mod deactivate;
-pub use deactivate::*;
-
 pub mod process_set_lockup_checked;
-pub use process_set_lockup_checked::*;
+pub mod tags;
+
+pub use initialize::{do_initialize, initialize, initialize as process_initialize};
+pub use initialize_checked::process_initialize_checked;
+pub use split::process_split;
+pub use process_set_lockup::{apply_lockup_update, process_set_lockup, process_set_lockup_parsed};
+pub use authorize::process_authorize;
+pub use authorize_checked::process_authorize_checked;
+pub use process_authorized_with_seeds::process_authorized_with_seeds;
+pub use process_authorize_checked_with_seed::process_authorize_checked_with_seed;
+pub use merge::process_merge;
+#[cfg(feature = "merge-many")]
+pub use merge_many::{process_merge_many, MERGE_MANY_TAG};
+pub use process_delegate::process_delegate;
+pub use process_move_stake::process_move_stake;
+pub use process_redelegate::redelegate;
+pub use deactivate_delinquent::process_deactivate_delinquent;
+pub use move_lamports::process_move_lamports;
+pub use withdraw::process_withdraw;
+pub use deactivate::process_deactivate;
+pub use process_set_lockup_checked::{process_set_lockup_checked, LockupCheckedData};
 
 #[repr(u8)]
 pub enum StakeInstruction {
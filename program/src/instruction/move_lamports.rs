@@ -1,8 +1,14 @@
 
 extern crate alloc;
 
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
-use crate::helpers::relocate_lamports;
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use crate::helpers::{assert_owned_by_program, assert_stake_sized, free_lamports, relocate_lamports};
+use crate::state::StakeHistorySysvar;
 use crate::helpers::merge::move_stake_or_lamports_shared_checks;
 use crate::state::merge_kind::MergeKind;
 
@@ -20,6 +26,12 @@ pub fn process_move_lamports(accounts: &[AccountInfo], lamports: u64) -> Program
     let [source_stake_ai, destination_stake_ai, staker_authority_ai] = accounts else {
         return Err(ProgramError::InvalidInstructionData);
     };
+    assert_owned_by_program(source_stake_ai)?;
+    assert_owned_by_program(destination_stake_ai)?;
+    // Moving into itself is rejected before any state is read
+    if source_stake_ai.key() == destination_stake_ai.key() {
+        return Err(ProgramError::InvalidArgument);
+    }
     // Resolve the expected staker key from source meta and ensure the 3rd account is that signer
     let src_state = crate::helpers::get_stake_state(source_stake_ai)?;
     let expected_staker = match src_state {
@@ -45,6 +57,10 @@ pub fn process_move_lamports(accounts: &[AccountInfo], lamports: u64) -> Program
     )?;
     // shared checks complete
 
+    // Same size safeguard as MoveStake
+    assert_stake_sized(source_stake_ai)?;
+    assert_stake_sized(destination_stake_ai)?;
+
     // Authorities/lockups compatibility were already enforced by shared checks.
 
     // (post-check logging removed; pre-check above handles transient)
@@ -54,30 +70,23 @@ pub fn process_move_lamports(accounts: &[AccountInfo], lamports: u64) -> Program
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Compute withdrawable lamports from source using the earlier classification
-    // - FullyActive: total - rent - max(delegated, min_delegation)
+    // Withdrawable lamports from source, using the earlier classification
+    // - FullyActive: total - rent - delegated
     // - Inactive (Initialized or post-deactivation): total - rent
     // - ActivationEpoch: reject (transient)
-    let source_free_lamports = {
-        let total = source_stake_ai.lamports();
-        match &source_kind {
-            MergeKind::Inactive(meta, _stake_lamports, _flags) => {
-                let rent_reserve = u64::from_le_bytes(meta.rent_exempt_reserve);
-                pinocchio::msg!("ml:inact");
-                total.saturating_sub(rent_reserve)
-            }
-            MergeKind::FullyActive(meta, stake) => {
-                let rent_reserve = u64::from_le_bytes(meta.rent_exempt_reserve);
-                let delegated = crate::helpers::bytes_to_u64(stake.delegation.stake);
-                if delegated == 0 { pinocchio::msg!("ml:deleg0"); } else { pinocchio::msg!("ml:delegN"); }
-                pinocchio::msg!("ml:fa");
-                // Native parity: free = total - rent - delegated
-                total.saturating_sub(rent_reserve).saturating_sub(delegated)
-            }
-            MergeKind::ActivationEpoch(_, _, _) => {
-                pinocchio::msg!("ml:transient_act");
-                return Err(crate::error::to_program_error(crate::error::StakeError::MergeMismatch));
-            }
+    let source_free_lamports = match &source_kind {
+        MergeKind::ActivationEpoch(_, _, _) => {
+            pinocchio::msg!("ml:transient_act");
+            return Err(crate::error::to_program_error(crate::error::StakeError::MergeMismatch));
+        }
+        MergeKind::Inactive(_, _, _) | MergeKind::FullyActive(_, _) => {
+            let clock = Clock::get()?;
+            free_lamports(
+                &src_state,
+                source_stake_ai.lamports(),
+                &clock,
+                &StakeHistorySysvar(clock.epoch),
+            )
         }
     };
     // Emit comparison markers for tests
@@ -107,5 +116,9 @@ pub fn process_move_lamports(accounts: &[AccountInfo], lamports: u64) -> Program
         return Err(ProgramError::InvalidArgument);
     }
 
+    // Confirm the moved amount to CPI callers
+    #[cfg(feature = "move-return-data")]
+    pinocchio::program::set_return_data(&lamports.to_le_bytes());
+
     Ok(())
 }
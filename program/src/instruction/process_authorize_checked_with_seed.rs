@@ -10,7 +10,7 @@ use pinocchio::{
 extern crate alloc;
 
 use crate::{
-    helpers::{authorize_update, get_stake_state, set_stake_state},
+    helpers::{authorize_update, find_custodian_signer, get_stake_state, set_stake_state},
     state::{
         accounts::AuthorizeCheckedWithSeedData,
         stake_state_v2::StakeStateV2,
@@ -39,6 +39,8 @@ fn derive_with_seed_compat(base: &Pubkey, seed: &[u8], owner: &Pubkey) -> Result
 ///   2. []         Clock sysvar
 ///   3. [signer]   New authority
 ///   4. [signer]   Optional custodian (required if lockup in force)
+///
+/// Both dispatch paths read the new authority from index 3; no other order is accepted.
 pub fn process_authorize_checked_with_seed(
     accounts: &[AccountInfo],
     args: AuthorizeCheckedWithSeedData,
@@ -96,7 +98,7 @@ pub fn process_authorize_checked_with_seed(
         StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _) => meta.lockup.is_in_force(&clock, None),
         _ => false,
     };
-    let maybe_custodian = rest.iter().find(|ai| ai.is_signer() && ai.key() == &custodian_pk);
+    let maybe_custodian = find_custodian_signer(rest, &custodian_pk);
     if matches!(role, StakeAuthorize::Withdrawer) && in_force && maybe_custodian.is_none() {
         pinocchio::msg!("acws:custodian_required_missing");
         return Err(ProgramError::MissingRequiredSignature);
@@ -7,7 +7,7 @@ use pinocchio::{
 };
 
 use crate::{
-    helpers::{get_stake_state, set_stake_state},
+    helpers::{find_custodian_signer, get_stake_state, set_stake_state},
     helpers::authorize_update,
     state::{
         accounts::AuthorizeWithSeedData,
@@ -55,6 +55,11 @@ pub fn process_authorized_with_seeds(
         Ok(s) => s,
         Err(e) => { pinocchio::msg!("aws:get_state_err"); return Err(e); }
     };
+    // Only Initialized/Stake carry authorities; fail before locating base or deriving
+    if !matches!(state, StakeStateV2::Initialized(_) | StakeStateV2::Stake(_, _, _)) {
+        pinocchio::msg!("aws:bad_state");
+        return Err(ProgramError::InvalidAccountData);
+    }
 
     // Determine expected custodian (to avoid mis-identifying it as base)
     let expected_custodian = match &state {
@@ -105,12 +110,11 @@ pub fn process_authorized_with_seeds(
     };
     if !allowed { pinocchio::msg!("aws:not_allowed"); return Err(ProgramError::MissingRequiredSignature); }
 
-    // Optional lockup custodian (scan trailing accounts for a matching signer)
-    let rest = &rest_all[..];
-    let maybe_lockup_authority: Option<&AccountInfo> = rest
-        .iter()
-        .find(|ai| ai.is_signer() && ai.key() == &expected_custodian);
-    if maybe_lockup_authority.is_some() { pinocchio::msg!("aws:custodian_present"); } else { pinocchio::msg!("aws:custodian_absent"); }
+    // Optional lockup custodian: a matching signer among the metas after base
+    let rest = &rest_all[base_idx.map_or(0, |i| i + 1)..];
+    let maybe_lockup_authority = find_custodian_signer(rest, &expected_custodian);
+    let custodian_signed = maybe_lockup_authority.is_some();
+    if custodian_signed { pinocchio::msg!("aws:custodian_present"); } else { pinocchio::msg!("aws:custodian_absent"); }
     
 
     // Restricted signer set: base (+ optional custodian) and, for derived authority, treat the current authority as signed
@@ -127,7 +131,7 @@ pub fn process_authorized_with_seeds(
         signers[n] = withdrawer_pk; n += 1;
     }
     // Include custodian if present as signer
-    if let Some(ai) = maybe_lockup_authority { signers[n] = *ai.key(); n += 1; }
+    if custodian_signed { signers[n] = expected_custodian; n += 1; }
     let signers = &signers[..n];
 
     // Apply policy update and write back
@@ -6,7 +6,7 @@ use pinocchio::{
 
 use crate::error::to_program_error;
 use crate::helpers::{
-    collect_signers, MAXIMUM_SIGNERS, validate_delegated_amount, ValidatedDelegatedInfo,
+    collect_signers, MAXIMUM_SIGNERS, validate_delegated_amount_min, ValidatedDelegatedInfo,
 };
 use crate::helpers::utils::{
     get_stake_state, get_vote_credits, new_stake_with_credits, redelegate_stake_with_credits,
@@ -45,10 +45,12 @@ pub fn process_delegate(accounts: &[AccountInfo]) -> ProgramResult {
         { pinocchio::msg!("delegate:bad_stake_history"); }
         return Err(ProgramError::InvalidInstructionData);
     }
-    // Optional 5th StakeConfig account accepted (shape parity), ignored if present
-    // if let Some(cfg) = rest.first() {
-    //     if cfg.key() != &crate::state::stake_config::ID { return Err(ProgramError::InvalidInstructionData); }
-    // }
+    // 5th account: StakeConfig slot. Accepted and ignored by default; checked
+    // for identity under `enforce-stake-config`.
+    #[cfg(feature = "enforce-stake-config")]
+    check_stake_config_slot(rest)?;
+    #[cfg(not(feature = "enforce-stake-config"))]
+    let _ = rest;
 
     let clock = &Clock::from_account_info(clock_info)?;
     let stake_history = &StakeHistorySysvar(clock.epoch);
@@ -62,15 +64,9 @@ pub fn process_delegate(accounts: &[AccountInfo]) -> ProgramResult {
                 .check(signers, StakeAuthorize::Staker)
                 .map_err(to_program_error)?;
 
-            // Amount delegated = lamports - rent_exempt_reserve
+            // Amount delegated = lamports - rent_exempt_reserve, at least the minimum
             let ValidatedDelegatedInfo { stake_amount } =
-                validate_delegated_amount(stake_account_info, &meta)?;
-
-            // Enforce minimum delegation at initial delegate time (native parity)
-            let min = crate::helpers::get_minimum_delegation();
-            if stake_amount < min {
-                return Err(to_program_error(crate::error::StakeError::InsufficientDelegation));
-            }
+                validate_delegated_amount_min(stake_account_info, &meta)?;
 
             // Create stake and store
             let stake = new_stake_with_credits(
@@ -82,7 +78,7 @@ pub fn process_delegate(accounts: &[AccountInfo]) -> ProgramResult {
 
             set_stake_state(
                 stake_account_info,
-                &StakeStateV2::Stake(meta, stake, StakeFlags::empty()),
+                &StakeStateV2::new_stake(meta, stake, StakeFlags::empty())?,
             )
         }
         StakeStateV2::Stake(meta, mut stake, flags) => {
@@ -91,14 +87,9 @@ pub fn process_delegate(accounts: &[AccountInfo]) -> ProgramResult {
                 .check(signers, StakeAuthorize::Staker)
                 .map_err(to_program_error)?;
 
+            // Amount delegated = lamports - rent_exempt_reserve, at least the minimum
             let ValidatedDelegatedInfo { stake_amount } =
-                validate_delegated_amount(stake_account_info, &meta)?;
-
-            // Enforce minimum delegation on redelegation as well for parity with native
-            let min = crate::helpers::get_minimum_delegation();
-            if stake_amount < min {
-                return Err(to_program_error(crate::error::StakeError::InsufficientDelegation));
-            }
+                validate_delegated_amount_min(stake_account_info, &meta)?;
 
             // If deactivation is scheduled and target vote differs, reject (TooSoon)
             // Pre-check: if deactivating, only allow redelegation to the same vote
@@ -118,10 +109,25 @@ pub fn process_delegate(accounts: &[AccountInfo]) -> ProgramResult {
                 stake_history,
             )?;
 
-            set_stake_state(stake_account_info, &StakeStateV2::Stake(meta, stake, flags))
+            set_stake_state(stake_account_info, &StakeStateV2::new_stake(meta, stake, flags)?)
         }
         _ => Err(ProgramError::InvalidAccountData),
     }?;
 
     Ok(())
 }
+
+/// Native order under `enforce-stake-config`:
+/// [stake, vote, clock, stake_history, stake_config, stake_authority, ...].
+/// The config slot must hold the stake-config id; a repeated stake-history
+/// sysvar in that slot (some clients fill it that way) stands in for it.
+#[cfg(feature = "enforce-stake-config")]
+fn check_stake_config_slot(rest: &[AccountInfo]) -> ProgramResult {
+    let config_ai = rest.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if config_ai.key() == &crate::state::stake_config::ID
+        || config_ai.key() == &crate::state::stake_history::ID
+    {
+        return Ok(());
+    }
+    Err(ProgramError::InvalidArgument)
+}
@@ -4,7 +4,10 @@ use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramR
 
 use crate::error::{to_program_error, StakeError};
 use crate::helpers::{
+    assert_owned_by_program,
+    assert_stake_sized,
     bytes_to_u64,
+    enforce_minimum_delegation,
     get_minimum_delegation,
     relocate_lamports, // use shared helper, not a local copy
     set_stake_state,
@@ -24,10 +27,11 @@ pub fn process_move_stake(accounts: &[AccountInfo], lamports: u64) -> ProgramRes
         return Err(ProgramError::InvalidInstructionData);
     };
     // Owner and writable checks with native-like error splits
-    if *source_stake_account_info.owner() != crate::ID
-        || *destination_stake_account_info.owner() != crate::ID
-    {
-        return Err(ProgramError::InvalidAccountOwner);
+    assert_owned_by_program(source_stake_account_info)?;
+    assert_owned_by_program(destination_stake_account_info)?;
+    // Moving into itself is rejected before any state is read
+    if source_stake_account_info.key() == destination_stake_account_info.key() {
+        return Err(ProgramError::InvalidArgument);
     }
     if !source_stake_account_info.is_writable() || !destination_stake_account_info.is_writable() {
         return Err(ProgramError::InvalidInstructionData);
@@ -75,11 +79,8 @@ pub fn process_move_stake(accounts: &[AccountInfo], lamports: u64) -> ProgramRes
     // Deactivating checks are handled inside shared_checks classification; no extra guard needed here.
 
     // Native safeguard: require exact account data size
-    if source_stake_account_info.data_len() != StakeStateV2::size_of()
-        || destination_stake_account_info.data_len() != StakeStateV2::size_of()
-    {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    assert_stake_sized(source_stake_account_info)?;
+    assert_stake_sized(destination_stake_account_info)?;
 
     // Source must be fully active
     let MergeKind::FullyActive(source_meta, mut source_stake) = source_kind else {
@@ -100,7 +101,7 @@ pub fn process_move_stake(accounts: &[AccountInfo], lamports: u64) -> ProgramRes
     }
 
     // destination must be fully active or fully inactive
-    // Capture existing flags for preservation
+    // Destination ends up with the union of both accounts' flags, as in merge
     let src_flags = match &src_state {
         StakeStateV2::Stake(_, _, f) => *f,
         _ => StakeFlags::empty(),
@@ -136,16 +137,14 @@ pub fn process_move_stake(accounts: &[AccountInfo], lamports: u64) -> ProgramRes
 
             set_stake_state(
                 destination_stake_account_info,
-                &StakeStateV2::Stake(destination_meta, destination_stake, StakeFlags::empty()),
+                &StakeStateV2::Stake(destination_meta, destination_stake, dest_existing_flags.union(src_flags)),
             )?;
 
             destination_meta
         }
         MergeKind::Inactive(destination_meta, _lamports, _flags) => {
-            // inactive destination must receive at least the minimum delegation
-            if lamports < minimum_delegation {
-                return Err(ProgramError::InvalidArgument);
-            }
+            // inactive destination must receive at least the minimum delegation (no dust delegations)
+            enforce_minimum_delegation(lamports)?;
 
             // clone source stake shape and set only the moved stake amount
             let mut destination_stake = source_stake;
@@ -153,7 +152,7 @@ pub fn process_move_stake(accounts: &[AccountInfo], lamports: u64) -> ProgramRes
 
             set_stake_state(
                 destination_stake_account_info,
-                &StakeStateV2::Stake(destination_meta, destination_stake, dest_existing_flags),
+                &StakeStateV2::Stake(destination_meta, destination_stake, dest_existing_flags.union(src_flags)),
             )?;
 
             destination_meta
@@ -190,5 +189,9 @@ pub fn process_move_stake(accounts: &[AccountInfo], lamports: u64) -> ProgramRes
         return Err(ProgramError::InvalidArgument);
     }
 
+    // Confirm the moved stake to CPI callers
+    #[cfg(feature = "move-return-data")]
+    pinocchio::program::set_return_data(&lamports.to_le_bytes());
+
     Ok(())
 }
@@ -8,10 +8,10 @@ use pinocchio::{
 
 use crate::{
     error::to_program_error,
-    helpers::{collect_signers, next_account_info},
+    helpers::{collect_signers, next_account_info, next_owned_account},
     helpers::utils::{
         get_stake_state, get_vote_credits, new_stake_with_credits, redelegate_stake_with_credits, set_stake_state,
-        validate_delegated_amount, ValidatedDelegatedInfo,
+        validate_delegated_amount_min, ValidatedDelegatedInfo,
     },
     helpers::constant::MAXIMUM_SIGNERS,
     state::{StakeAuthorize, StakeFlags, StakeHistorySysvar, StakeStateV2},
@@ -26,14 +26,14 @@ pub fn redelegate(accounts: &[AccountInfo]) -> ProgramResult {
 
     // Expected accounts: 4 or 5 (native shape) -> [stake, vote, clock, stake_history, (optional stake_config)]
     let account_info_iter = &mut accounts.iter();
-    let stake_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_owned_account(account_info_iter, &crate::ID)?;
     let vote_account_info  = next_account_info(account_info_iter)?;
     let clock_info         = next_account_info(account_info_iter)?;
     let stake_history_ai   = next_account_info(account_info_iter)?; // present but not read directly
     let _maybe_stake_config_ai = account_info_iter.next(); // optional and not read directly
 
     // Ownership/identity checks for native parity
-    if *stake_account_info.owner() != crate::ID || !stake_account_info.is_writable() {
+    if !stake_account_info.is_writable() {
         return Err(ProgramError::InvalidAccountOwner);
     }
     if *vote_account_info.owner() != crate::state::vote_state::vote_program_id() {
@@ -61,15 +61,9 @@ pub fn redelegate(accounts: &[AccountInfo]) -> ProgramResult {
                 .check(signers, StakeAuthorize::Staker)
                 .map_err(to_program_error)?;
 
-            // how much can be delegated (lamports - rent)
+            // Amount delegated = lamports - rent_exempt_reserve, at least the minimum
             let ValidatedDelegatedInfo { stake_amount } =
-                validate_delegated_amount(stake_account_info, &meta)?;
-
-            // Enforce minimum delegation at (re)delegate time (native parity)
-            let min = crate::helpers::get_minimum_delegation();
-            if stake_amount < min {
-                return Err(to_program_error(crate::error::StakeError::InsufficientDelegation));
-            }
+                validate_delegated_amount_min(stake_account_info, &meta)?;
 
             // create stake delegated to the vote account
             let stake = new_stake_with_credits(
@@ -90,14 +84,9 @@ pub fn redelegate(accounts: &[AccountInfo]) -> ProgramResult {
                 .check(signers, StakeAuthorize::Staker)
                 .map_err(to_program_error)?;
 
+            // Amount delegated = lamports - rent_exempt_reserve, at least the minimum
             let ValidatedDelegatedInfo { stake_amount } =
-                validate_delegated_amount(stake_account_info, &meta)?;
-
-            // Enforce minimum delegation on redelegation when inactive (native parity)
-            let min = crate::helpers::get_minimum_delegation();
-            if stake_amount < min {
-                return Err(to_program_error(crate::error::StakeError::InsufficientDelegation));
-            }
+                validate_delegated_amount_min(stake_account_info, &meta)?;
 
             // Mirror explicit TooSoon pre-check: if deactivating and target vote differs, reject
             let current_voter = stake.delegation.voter_pubkey;
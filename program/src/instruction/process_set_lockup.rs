@@ -7,7 +7,7 @@ use pinocchio::{
 };
 
 use crate::{
-    helpers::{collect_signers, next_account_info},
+    helpers::{collect_signers, next_writable_account},
     helpers::utils::{get_stake_state, set_stake_state},
     helpers::constant::MAXIMUM_SIGNERS,
     state::{accounts::SetLockupData, stake_state_v2::StakeStateV2, state::Meta},
@@ -54,7 +54,8 @@ fn parse_set_lockup_bytes(data: &[u8]) -> Result<SetLockupData, ProgramError> {
 pub fn process_set_lockup(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
     // Iterate accounts: first is stake; additional accounts may be supplied
     let account_info_iter = &mut accounts.iter();
-    let stake_account_info = next_account_info(account_info_iter)?;
+    // Reject a readonly stake account before reading any state
+    let stake_account_info = next_writable_account(account_info_iter)?;
     // Additional accounts are considered for signer collection
 
     // Parse payload into optional fields (wire-safe flags+payloads)
@@ -68,7 +69,7 @@ pub fn process_set_lockup(accounts: &[AccountInfo], instruction_data: &[u8]) ->
     let n = collect_signers(accounts, &mut signer_buf)?;
     let signers = &signer_buf[..n];
 
-    // Owner and size checks are performed by get_stake_state(); writable is enforced by set_stake_state
+    // Owner and size checks are performed by get_stake_state()
     match get_stake_state(stake_account_info)? {
         StakeStateV2::Initialized(mut meta) => {
             apply_lockup_update(&mut meta, &args, &clock, signers)?;
@@ -92,7 +93,7 @@ pub fn process_set_lockup_parsed(
 ) -> ProgramResult {
     // Iterate accounts: first is stake
     let account_info_iter = &mut accounts.iter();
-    let stake_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_writable_account(account_info_iter)?;
 
     // Read the clock sysvar directly (no clock account required)
     let clock = Clock::get()?;
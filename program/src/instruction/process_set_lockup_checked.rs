@@ -9,7 +9,7 @@ use pinocchio::{
 };
 
 use crate::{
-    helpers::{get_stake_state, set_stake_state},
+    helpers::{get_stake_state, require_signer, set_stake_state},
     state::{stake_state_v2::StakeStateV2, state::Meta},
 };
 
@@ -24,8 +24,12 @@ impl LockupCheckedData {
         if data.is_empty() {
             return Err(ProgramError::InvalidInstructionData);
         }
+        // flags == 0 (no timestamp, no epoch) is valid: native still authorizes the
+        // signer but leaves the lockup unchanged.
         let flags = data[0];
-        // Only timestamp (0x01) and epoch (0x02) are valid for the checked variant
+        // Only timestamp (0x01) and epoch (0x02) are valid for the checked variant:
+        // a new custodian is supplied as a signer account, never in the payload
+        // (the unchecked SetLockup's 0x04 bit)
         if flags & !0x03 != 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
@@ -101,10 +105,7 @@ pub fn process_set_lockup_checked(
             return Err(e);
         }
     };
-    // No need to scan remaining metas here; dispatch enforces signer policy.
-    let _rest = &accounts[1..];
-
-    let _clock = Clock::get()?;
+    let clock = Clock::get()?;
 
     let state = get_stake_state(stake_ai)?;
     #[cfg(feature = "cu-trace")]
@@ -114,9 +115,24 @@ pub fn process_set_lockup_checked(
         StakeStateV2::Stake(_, _, _) => pinocchio::msg!("slc:state=Stake"),
         StakeStateV2::RewardsPool => pinocchio::msg!("slc:state=RewardsPool"),
     };
-    // Do not derive or validate signer roles here; dispatch handled it.
 
-    // Keep handler lean; dispatch enforces signer policy.
+    // Role signer from the state loaded above: custodian while the lockup is
+    // in force, withdrawer otherwise. Any meta position is accepted.
+    match &state {
+        StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _) => {
+            let role = if meta.lockup.is_in_force(&clock, None) {
+                &meta.lockup.custodian
+            } else {
+                &meta.authorized.withdrawer
+            };
+            require_signer(accounts, role)?;
+        }
+        // No lockup to set: rejected before any signer is looked at, as native does
+        _ => {
+            pinocchio::msg!("slc:bad_state");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
 
     match state {
         StakeStateV2::Initialized(mut meta) => {
@@ -125,7 +141,7 @@ pub fn process_set_lockup_checked(
                 checked.unix_timestamp,
                 checked.epoch,
                 stake_ai,
-                &_clock,
+                &clock,
             )?;
             // Native checked semantics: do not modify custodian here
             set_stake_state(stake_ai, &StakeStateV2::Initialized(meta))?;
@@ -136,16 +152,13 @@ pub fn process_set_lockup_checked(
                 checked.unix_timestamp,
                 checked.epoch,
                 stake_ai,
-                &_clock,
+                &clock,
             )?;
             // Native checked semantics: do not modify custodian here
             set_stake_state(stake_ai, &StakeStateV2::Stake(meta, stake, flags))?;
         }
-        _ => {
-            #[cfg(feature = "cu-trace")]
-            pinocchio::msg!("slc:state_bad_noop");
-            // Treat as no-op to match native tolerance in ProgramTest
-        },
+        // Rejected with the signer check above
+        _ => {}
     }
 
     Ok(())
@@ -168,3 +181,28 @@ fn apply_set_lockup_policy_checked(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_flags_limited_to_timestamp_and_epoch() {
+        let mut data = [0u8; 17];
+        data[0] = 0x03;
+        data[1..9].copy_from_slice(&(-5i64).to_le_bytes());
+        data[9..17].copy_from_slice(&9u64.to_le_bytes());
+        let parsed = LockupCheckedData::parse(&data).unwrap();
+        assert_eq!(parsed.unix_timestamp, Some(-5));
+        assert_eq!(parsed.epoch, Some(9));
+        assert!(LockupCheckedData::parse(&[0x00]).is_ok());
+
+        // 0x04 is a valid custodian bit for unchecked SetLockup (mask 0x07), not here
+        let mut with_custodian = [0u8; 33];
+        with_custodian[0] = 0x04;
+        assert_eq!(LockupCheckedData::parse(&with_custodian).err(), Some(ProgramError::InvalidInstructionData));
+        for flags in [0x04u8, 0x08, 0x10, 0x80, 0xff] {
+            assert_eq!(LockupCheckedData::parse(&[flags]).err(), Some(ProgramError::InvalidInstructionData), "flags {:#04x}", flags);
+        }
+    }
+}
@@ -16,7 +16,8 @@ pub fn process_split(accounts: &[AccountInfo], split_lamports: u64) -> ProgramRe
     let mut arr_of_signers = [Pubkey::default(); MAXIMUM_SIGNERS];
     let _ = collect_signers(accounts, &mut arr_of_signers)?;
 
-    // Canonical SDK order: [source_stake, destination_stake, stake_authority]
+    // Canonical SDK order: [source_stake, destination_stake, stake_authority, ...]; trailing
+    // accounts (system program, rent, ...) are ignored and never read as the authority
     if accounts.len() < 3 { return Err(ProgramError::NotEnoughAccountKeys); }
     let source_stake_account_info = &accounts[0];
     let destination_stake_account_info = &accounts[1];
@@ -29,34 +30,17 @@ pub fn process_split(accounts: &[AccountInfo], split_lamports: u64) -> ProgramRe
     if !authority_account_info.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
-    if *source_stake_account_info.owner() != crate::ID {
-        return Err(ProgramError::InvalidAccountOwner);
-    }
-    if *destination_stake_account_info.owner() != crate::ID {
-        return Err(ProgramError::InvalidAccountOwner);
+    assert_owned_by_program(source_stake_account_info)?;
+    assert_owned_by_program(destination_stake_account_info)?;
+    // Splitting into itself is invalid for every source state, Uninitialized included
+    if source_stake_account_info.key() == destination_stake_account_info.key() {
+        return Err(ProgramError::InvalidArgument);
     }
 
     let clock = Clock::get()?;
     let stake_history = &StakeHistorySysvar(clock.epoch);
 
-    let source_lamport_balance = source_stake_account_info.lamports();
-
-    // Global preflight: fail fast for oversplit before touching destination
-    pinocchio::msg!("split:preflight_enter");
-    if split_lamports > source_lamport_balance {
-        pinocchio::msg!("split:preflight_over_balance");
-        return Err(ProgramError::InsufficientFunds);
-    }
-    // Rent-reserve preflight applies to Initialized/Stake; Uninitialized is handled below.
-    pinocchio::msg!("split:preflight_ok");
-
-    let destination_lamport_balance = destination_stake_account_info.lamports();
-
-    // Skip rent-reserve preflight for Uninitialized; handled in match arm.
-
-    // note: over-balance already checked in preflight above
-
-    // Validate destination after basic over-balance check so initial errors map to InsufficientFunds
+    // Native error order: destination size, then destination state, then over-balance
     let destination_data_len = destination_stake_account_info.data_len();
     // Native requires exact account data size
     if destination_data_len != StakeStateV2::size_of() {
@@ -71,12 +55,19 @@ pub fn process_split(accounts: &[AccountInfo], split_lamports: u64) -> ProgramRe
             return Err(ProgramError::InvalidAccountData)
         }
     }
+    let destination_lamport_balance = destination_stake_account_info.lamports();
+
+    let source_lamport_balance = source_stake_account_info.lamports();
+
+    // Oversplit fails before the source state is examined
+    if split_lamports > source_lamport_balance {
+        pinocchio::msg!("split:preflight_over_balance");
+        return Err(ProgramError::InsufficientFunds);
+    }
+    // Rent-reserve checks apply to Initialized/Stake; Uninitialized is handled in its arm.
 
     match get_stake_state(source_stake_account_info)? {
         StakeStateV2::Stake(source_meta, mut source_stake, stake_flags) => {
-            if source_stake_account_info.key() == destination_stake_account_info.key() {
-                return Err(ProgramError::InvalidArgument);
-            }
             // Enforce index-2 is the staker and has signed
             if source_meta.authorized.staker != *authority_account_info.key() {
                 return Err(ProgramError::MissingRequiredSignature);
@@ -157,20 +148,20 @@ pub fn process_split(accounts: &[AccountInfo], split_lamports: u64) -> ProgramRe
                 .destination_rent_exempt_reserve
                 .to_le_bytes();
 
-            set_stake_state(
-                source_stake_account_info,
-                &StakeStateV2::Stake(source_meta, source_stake, stake_flags),
-            )?;
+            // A full split leaves no stake behind; the source is deinitialized below
+            if validated_split_info.source_remaining_balance != 0 {
+                set_stake_state(
+                    source_stake_account_info,
+                    &StakeStateV2::new_stake(source_meta, source_stake, stake_flags)?,
+                )?;
+            }
 
             set_stake_state(
                 destination_stake_account_info,
-                &StakeStateV2::Stake(destination_meta, destination_stake, stake_flags),
+                &StakeStateV2::new_stake(destination_meta, destination_stake, stake_flags)?,
             )?;
         }
         StakeStateV2::Initialized(source_meta) => {
-            if source_stake_account_info.key() == destination_stake_account_info.key() {
-                return Err(ProgramError::InvalidArgument);
-            }
             // Enforce index-2 is the staker and has signed
             if source_meta.authorized.staker != *authority_account_info.key() {
                 return Err(ProgramError::MissingRequiredSignature);
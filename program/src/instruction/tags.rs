@@ -0,0 +1,76 @@
+//! Native `StakeInstruction` discriminants, as used by the entrypoint's
+//! short-tag (first byte only) dispatch. Bincode encodes the same value as
+//! the low byte of a u32 LE variant index.
+
+pub const TAG_INITIALIZE: u8 = 0;
+pub const TAG_AUTHORIZE: u8 = 1;
+pub const TAG_DELEGATE: u8 = 2;
+pub const TAG_SPLIT: u8 = 3;
+pub const TAG_WITHDRAW: u8 = 4;
+pub const TAG_DEACTIVATE: u8 = 5;
+pub const TAG_SET_LOCKUP: u8 = 6;
+pub const TAG_MERGE: u8 = 7;
+pub const TAG_AUTHORIZE_WITH_SEED: u8 = 8;
+pub const TAG_INITIALIZE_CHECKED: u8 = 9;
+pub const TAG_AUTHORIZE_CHECKED: u8 = 10;
+pub const TAG_AUTHORIZE_CHECKED_WITH_SEED: u8 = 11;
+pub const TAG_SET_LOCKUP_CHECKED: u8 = 12;
+pub const TAG_GET_MINIMUM_DELEGATION: u8 = 13;
+pub const TAG_DEACTIVATE_DELINQUENT: u8 = 14;
+pub const TAG_REDELEGATE: u8 = 15;
+pub const TAG_MOVE_STAKE: u8 = 16;
+pub const TAG_MOVE_LAMPORTS: u8 = 17;
+
+/// Non-native tags some SDK builds emitted for DeactivateDelinquent; only
+/// honoured under `compat_loose_decode`.
+pub const TAG_DEACTIVATE_DELINQUENT_DRIFT_FIRST: u8 = 18;
+pub const TAG_DEACTIVATE_DELINQUENT_DRIFT_LAST: u8 = 21;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::StakeInstruction;
+    use solana_sdk::stake::instruction::{LockupArgs, StakeInstruction as SdkIx};
+    use solana_sdk::stake::state::{Authorized, Lockup, StakeAuthorize};
+
+    #[test]
+    #[allow(deprecated)]
+    fn tags_match_native_discriminants() {
+        let seed_args = solana_sdk::stake::instruction::AuthorizeWithSeedArgs {
+            new_authorized_pubkey: Default::default(),
+            stake_authorize: StakeAuthorize::Staker,
+            authority_seed: String::new(),
+            authority_owner: Default::default(),
+        };
+        let checked_seed_args = solana_sdk::stake::instruction::AuthorizeCheckedWithSeedArgs {
+            stake_authorize: StakeAuthorize::Staker,
+            authority_seed: String::new(),
+            authority_owner: Default::default(),
+        };
+        let cases = [
+            (TAG_INITIALIZE, SdkIx::Initialize(Authorized::default(), Lockup::default())),
+            (TAG_AUTHORIZE, SdkIx::Authorize(Default::default(), StakeAuthorize::Staker)),
+            (TAG_DELEGATE, SdkIx::DelegateStake),
+            (TAG_SPLIT, SdkIx::Split(0)),
+            (TAG_WITHDRAW, SdkIx::Withdraw(0)),
+            (TAG_DEACTIVATE, SdkIx::Deactivate),
+            (TAG_SET_LOCKUP, SdkIx::SetLockup(LockupArgs::default())),
+            (TAG_MERGE, SdkIx::Merge),
+            (TAG_AUTHORIZE_WITH_SEED, SdkIx::AuthorizeWithSeed(seed_args)),
+            (TAG_INITIALIZE_CHECKED, SdkIx::InitializeChecked),
+            (TAG_AUTHORIZE_CHECKED, SdkIx::AuthorizeChecked(StakeAuthorize::Staker)),
+            (TAG_AUTHORIZE_CHECKED_WITH_SEED, SdkIx::AuthorizeCheckedWithSeed(checked_seed_args)),
+            (TAG_SET_LOCKUP_CHECKED, SdkIx::SetLockupChecked(Default::default())),
+            (TAG_GET_MINIMUM_DELEGATION, SdkIx::GetMinimumDelegation),
+            (TAG_DEACTIVATE_DELINQUENT, SdkIx::DeactivateDelinquent),
+            (TAG_REDELEGATE, SdkIx::Redelegate),
+            (TAG_MOVE_STAKE, SdkIx::MoveStake(0)),
+            (TAG_MOVE_LAMPORTS, SdkIx::MoveLamports(0)),
+        ];
+        for (tag, ix) in cases {
+            let bytes = bincode::serialize(&ix).unwrap();
+            assert_eq!(bytes[..4], (tag as u32).to_le_bytes(), "{:?}", ix);
+            assert_eq!(StakeInstruction::try_from(&tag).map(|i| i as u8), Ok(tag));
+        }
+    }
+}
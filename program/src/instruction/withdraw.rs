@@ -7,7 +7,10 @@ use pinocchio::{
 
 use crate::{
     error::{to_program_error, StakeError},
-    helpers::{checked_add, get_stake_state, relocate_lamports, set_stake_state},
+    helpers::{
+        assert_owned_by_program, checked_add, find_custodian_signer, get_stake_state,
+        relocate_lamports, set_stake_state, staked_lamports,
+    },
     state::{Lockup, StakeAuthorize, StakeHistorySysvar, StakeStateV2},
 
 };
@@ -32,7 +35,8 @@ pub fn process_withdraw(accounts: &[AccountInfo], withdraw_lamports: u64) -> Pro
     ] = accounts else { return Err(ProgramError::NotEnoughAccountKeys) };
 
     // Basic checks on key roles
-    if *source_stake_account_info.owner() != crate::ID || !source_stake_account_info.is_writable() {
+    assert_owned_by_program(source_stake_account_info)?;
+    if !source_stake_account_info.is_writable() {
         return Err(ProgramError::InvalidAccountOwner);
     }
     if !destination_info.is_writable() {
@@ -58,7 +62,26 @@ pub fn process_withdraw(accounts: &[AccountInfo], withdraw_lamports: u64) -> Pro
 
     // Decide withdrawal constraints based on current stake state
     #[cfg(feature = "cu-trace")] msg!("Withdraw: read state");
-    let (lockup, reserve_u64, is_staked) = match get_stake_state(source_stake_account_info)? {
+    let stake_account_lamports = source_stake_account_info.lamports();
+    let state = get_stake_state(source_stake_account_info)?;
+
+    // Fast path: closing an Initialized account that never had a lockup. Nothing
+    // is staked and no lockup can be in force, so skip the custodian scan and
+    // reserve math and only require the withdrawer.
+    if withdraw_lamports == stake_account_lamports {
+        if let StakeStateV2::Initialized(meta) = &state {
+            if meta.lockup == Lockup::default() {
+                #[cfg(feature = "cu-trace")] msg!("Withdraw: close fast path");
+                meta.authorized
+                    .check(signers_slice, StakeAuthorize::Withdrawer)
+                    .map_err(to_program_error)?;
+                set_stake_state(source_stake_account_info, &StakeStateV2::Uninitialized)?;
+                return relocate_lamports(source_stake_account_info, destination_info, withdraw_lamports);
+            }
+        }
+    }
+
+    let (lockup, reserve, is_staked) = match &state {
         StakeStateV2::Stake(meta, stake, _stake_flags) => {
             #[cfg(feature = "cu-trace")] msg!("Withdraw: state=Stake");
             // Must have withdraw authority
@@ -66,21 +89,10 @@ pub fn process_withdraw(accounts: &[AccountInfo], withdraw_lamports: u64) -> Pro
                 .check(signers_slice, StakeAuthorize::Withdrawer)
                 .map_err(to_program_error)?;
 
-            // At or past deactivation epoch, use dynamic effective stake
-            let deact_epoch = u64::from_le_bytes(stake.delegation.deactivation_epoch);
-            let staked: u64 = if deact_epoch != u64::MAX && clock.epoch >= deact_epoch {
-                stake.delegation.stake(
-                    clock.epoch.to_le_bytes(),
-                    stake_history,
-                    crate::helpers::PERPETUAL_NEW_WARMUP_COOLDOWN_RATE_EPOCH,
-                )
-            } else {
-                u64::from_le_bytes(stake.delegation.stake)
-            };
-
+            // At or past deactivation epoch the staked portion is the dynamic effective stake
+            let staked = staked_lamports(stake, clock, stake_history);
             let rent_reserve = u64::from_le_bytes(meta.rent_exempt_reserve);
-            let staked_plus_reserve = checked_add(staked, rent_reserve)?;
-            (meta.lockup, staked_plus_reserve, staked != 0)
+            (meta.lockup, checked_add(staked, rent_reserve)?, staked != 0)
         }
         StakeStateV2::Initialized(meta) => {
             #[cfg(feature = "cu-trace")] msg!("Withdraw: state=Initialized");
@@ -89,8 +101,7 @@ pub fn process_withdraw(accounts: &[AccountInfo], withdraw_lamports: u64) -> Pro
                 .check(signers_slice, StakeAuthorize::Withdrawer)
                 .map_err(to_program_error)?;
 
-            let rent_reserve = u64::from_le_bytes(meta.rent_exempt_reserve);
-            (meta.lockup, rent_reserve, false)
+            (meta.lockup, u64::from_le_bytes(meta.rent_exempt_reserve), false)
         }
         StakeStateV2::Uninitialized => {
             // Native fast-path: only the source stake account must sign
@@ -104,17 +115,12 @@ pub fn process_withdraw(accounts: &[AccountInfo], withdraw_lamports: u64) -> Pro
         _ => return Err(ProgramError::InvalidAccountData),
     };
 
-    // Lockup must be expired or bypassed by a custodian signer (scan trailing accounts for matching custodian)
-    let custodian = rest
-        .iter()
-        .find(|ai| ai.is_signer() && ai.key() == &lockup.custodian)
-        .map(|ai| ai.key());
+    // Lockup must be expired or bypassed by a custodian signer among the trailing accounts
+    let custodian = find_custodian_signer(rest, &lockup.custodian).map(|ai| ai.key());
     if lockup.is_in_force(clock, custodian) {
         return Err(to_program_error(StakeError::LockupInForce));
     }
 
-    let stake_account_lamports = source_stake_account_info.lamports();
-
     if withdraw_lamports == stake_account_lamports {
         #[cfg(feature = "cu-trace")] msg!("Withdraw: full");
         // Full withdrawal: can't close if still staked
@@ -125,9 +131,10 @@ pub fn process_withdraw(accounts: &[AccountInfo], withdraw_lamports: u64) -> Pro
         set_stake_state(source_stake_account_info, &StakeStateV2::Uninitialized)?;
     } else {
         #[cfg(feature = "cu-trace")] msg!("Withdraw: partial");
-        // Partial withdrawal must not deplete the reserve
-        let withdraw_plus_reserve = checked_add(withdraw_lamports, reserve_u64)?;
-        if withdraw_plus_reserve > stake_account_lamports {
+        // Partial withdrawal must not deplete the reserve or the stake. Compared
+        // against the balance, not a saturated free amount, so an account funded
+        // below reserve + stake refuses every partial withdraw, as native does.
+        if checked_add(withdraw_lamports, reserve)? > stake_account_lamports {
             return Err(ProgramError::InsufficientFunds);
         }
     }
@@ -13,6 +13,13 @@ pub mod instruction;
 pub mod state;
 pub mod crypto;
 
+// Native StakeInstruction wire types: bincode/serde on std, a manual reader
+// for SBF. The reader also builds for host tests so both decoders are checked.
+#[cfg(all(feature = "wire_bincode", feature = "std"))]
+pub(crate) mod wire;
+#[cfg(all(feature = "wire_bincode", any(test, not(feature = "std"))))]
+pub(crate) mod wire_sbf;
+
 pinocchio_pubkey::declare_id!("Stake11111111111111111111111111111111111111");
 
 // ---- SBF-only runtime shims (no_std builds) ----
@@ -1,7 +1,11 @@
 use crate::error::StakeError;
 use crate::helpers::{
-    bytes_to_u64, warmup_cooldown_rate, Epoch, DEFAULT_WARMUP_COOLDOWN_RATE,
+    bytes_to_u64, Epoch, DEFAULT_WARMUP_COOLDOWN_RATE, WARMUP_COOLDOWN_RATE_BPS_DENOMINATOR,
 };
+#[cfg(not(feature = "fixed-point-warmup"))]
+use crate::helpers::warmup_cooldown_rate;
+#[cfg(feature = "fixed-point-warmup")]
+use crate::helpers::warmup_cooldown_rate_bps;
 use crate::state::stake_history::{StakeHistoryEntry, StakeHistoryGetEntry, StakeHistorySysvar};
 use pinocchio::pubkey::Pubkey;
 
@@ -100,20 +104,15 @@ impl Delegation {
                     break;
                 }
 
-                // proportion of newly non-effective cluster stake this account is entitled to take
-                let weight = current_effective_stake as f64
-                    / bytes_to_u64(prev_cluster_stake.deactivating) as f64;
-                let rate = warmup_cooldown_rate(
-                    current_epoch_u64.to_le_bytes(),
+                // this account's share of the newly non-effective cluster stake
+                let newly_not_effective_stake = newly_changed_stake(
+                    current_effective_stake,
+                    bytes_to_u64(prev_cluster_stake.deactivating),
+                    bytes_to_u64(prev_cluster_stake.effective),
+                    current_epoch_u64,
                     new_rate_activation_epoch,
                 );
 
-                // newly not-effective cluster stake at current epoch
-                let newly_not_effective_cluster_stake =
-                    bytes_to_u64(prev_cluster_stake.effective) as f64 * rate;
-                let newly_not_effective_stake =
-                    ((weight * newly_not_effective_cluster_stake) as u64).max(1);
-
                 current_effective_stake = current_effective_stake.saturating_sub(newly_not_effective_stake);
                 if current_effective_stake == 0 {
                     break;
@@ -177,18 +176,14 @@ impl Delegation {
                 // entitlement to newly-effective cluster stake at current epoch
                 let delegated_stake_u64 = bytes_to_u64(delegated_stake);
                 let remaining_activating_stake = delegated_stake_u64 - current_effective_stake;
-                let weight = remaining_activating_stake as f64
-                    / bytes_to_u64(prev_cluster_stake.activating) as f64;
-                let rate = warmup_cooldown_rate(
-                    current_epoch_u64.to_le_bytes(),
+                let newly_effective_stake = newly_changed_stake(
+                    remaining_activating_stake,
+                    bytes_to_u64(prev_cluster_stake.activating),
+                    bytes_to_u64(prev_cluster_stake.effective),
+                    current_epoch_u64,
                     new_rate_activation_epoch,
                 );
 
-                let newly_effective_cluster_stake =
-                    bytes_to_u64(prev_cluster_stake.effective) as f64 * rate;
-                let newly_effective_stake =
-                    ((weight * newly_effective_cluster_stake) as u64).max(1);
-
                 current_effective_stake = current_effective_stake.saturating_add(newly_effective_stake);
                 if current_effective_stake >= delegated_stake_u64 {
                     current_effective_stake = delegated_stake_u64;
@@ -213,6 +208,51 @@ impl Delegation {
     }
 }
 
+/// Lamports of `stake` that (de)activate this epoch: its share of
+/// `cluster_changing`, applied to `cluster_effective * rate`, at least 1.
+/// With `fixed-point-warmup` the rate is applied in basis points, avoiding
+/// emulated float ops on SBF; results agree with the `f64` path to within a lamport.
+#[inline]
+fn newly_changed_stake(
+    stake: u64,
+    cluster_changing: u64,
+    cluster_effective: u64,
+    epoch: u64,
+    new_rate_activation_epoch: Option<Epoch>,
+) -> u64 {
+    #[cfg(not(feature = "fixed-point-warmup"))]
+    {
+        let rate = warmup_cooldown_rate(epoch.to_le_bytes(), new_rate_activation_epoch);
+        newly_changed_stake_f64(stake, cluster_changing, cluster_effective, rate)
+    }
+    #[cfg(feature = "fixed-point-warmup")]
+    {
+        let rate_bps = warmup_cooldown_rate_bps(epoch.to_le_bytes(), new_rate_activation_epoch);
+        newly_changed_stake_fixed(stake, cluster_changing, cluster_effective, rate_bps)
+    }
+}
+
+#[cfg_attr(feature = "fixed-point-warmup", allow(dead_code))]
+fn newly_changed_stake_f64(stake: u64, cluster_changing: u64, cluster_effective: u64, rate: f64) -> u64 {
+    let weight = stake as f64 / cluster_changing as f64;
+    let newly_changed_cluster_stake = cluster_effective as f64 * rate;
+    ((weight * newly_changed_cluster_stake) as u64).max(1)
+}
+
+#[cfg_attr(not(feature = "fixed-point-warmup"), allow(dead_code))]
+fn newly_changed_stake_fixed(stake: u64, cluster_changing: u64, cluster_effective: u64, rate_bps: u64) -> u64 {
+    let num = stake as u128 * cluster_effective as u128;
+    let changing = cluster_changing as u128;
+    let bps = rate_bps as u128;
+    let den = WARMUP_COOLDOWN_RATE_BPS_DENOMINATOR as u128;
+    let newly = match num.checked_mul(bps) {
+        Some(n) => n / changing.saturating_mul(den),
+        // Only reachable when stake exceeds the cluster total; divide first
+        None => (num / changing).saturating_mul(bps) / den,
+    };
+    u64::try_from(newly).unwrap_or(u64::MAX).max(1)
+}
+
 impl Default for Delegation {
     fn default() -> Self {
         #[allow(deprecated)]
@@ -269,11 +309,11 @@ impl Stake {
         Ok(new)
     }
 
-    pub fn deactivate(&mut self, epoch: Epoch) -> Result<(), StakeError> {
+    pub fn deactivate(&mut self, epoch: u64) -> Result<(), StakeError> {
         if bytes_to_u64(self.delegation.deactivation_epoch) != u64::MAX {
             Err(StakeError::AlreadyDeactivated)
         } else {
-            self.delegation.deactivation_epoch = epoch;
+            self.delegation.deactivation_epoch = epoch.to_le_bytes();
             Ok(())
         }
     }
@@ -294,3 +334,109 @@ impl Delegation {
         self.stake = amount.to_le_bytes();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        DEFAULT_WARMUP_COOLDOWN_RATE_BPS, NEW_WARMUP_COOLDOWN_RATE, NEW_WARMUP_COOLDOWN_RATE_BPS,
+    };
+
+    // f64 keeps ~53 bits; allow one lamport plus relative float error on huge values
+    fn assert_close(fixed: u64, float: u64) {
+        let tolerance = 1 + float / (1 << 40);
+        assert!(fixed.abs_diff(float) <= tolerance, "fixed {} vs f64 {}", fixed, float);
+    }
+
+    #[test]
+    fn fixed_point_matches_f64_on_random_inputs() {
+        let mut x = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        };
+        for (rate, bps) in [
+            (DEFAULT_WARMUP_COOLDOWN_RATE, DEFAULT_WARMUP_COOLDOWN_RATE_BPS),
+            (NEW_WARMUP_COOLDOWN_RATE, NEW_WARMUP_COOLDOWN_RATE_BPS),
+        ] {
+            for _ in 0..10_000 {
+                // Up to ~5.8e17 lamports of cluster stake (total supply order)
+                let cluster_changing = (next() >> 5).max(1);
+                let stake = next() % cluster_changing + 1;
+                let cluster_effective = next() >> 5;
+                assert_close(
+                    newly_changed_stake_fixed(stake, cluster_changing, cluster_effective, bps),
+                    newly_changed_stake_f64(stake, cluster_changing, cluster_effective, rate),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_point_warmup_tracks_f64_across_epochs() {
+        // One account activating alongside the rest of the cluster, epoch by epoch
+        for (ours, cluster_activating, cluster_effective) in [
+            (1_000u64, 1_000u64, 10_000u64),
+            (5_000_000_000, 80_000_000_000_000, 400_000_000_000_000_000),
+            (1, 3, 7),
+        ] {
+            let (mut fixed, mut float) = (0u64, 0u64);
+            let (mut activating, mut effective) = (cluster_activating, cluster_effective);
+            for epoch in 0..200u64 {
+                if fixed == ours && float == ours {
+                    break;
+                }
+                fixed = (fixed + newly_changed_stake_fixed(ours - fixed, activating, effective, NEW_WARMUP_COOLDOWN_RATE_BPS)).min(ours);
+                float = (float + newly_changed_stake_f64(ours - float, activating, effective, NEW_WARMUP_COOLDOWN_RATE)).min(ours);
+                // Rounding may compound by at most a lamport per elapsed epoch
+                assert!(fixed.abs_diff(float) <= epoch + 1 + float / (1 << 40), "epoch {}: {} vs {}", epoch, fixed, float);
+                let step = ((effective as f64 * NEW_WARMUP_COOLDOWN_RATE) as u64).clamp(1, activating);
+                effective += step;
+                activating = (activating - step).max(1);
+            }
+            assert_eq!(fixed, ours);
+            assert_eq!(float, ours);
+        }
+    }
+
+    #[test]
+    fn stake_matches_activation_status_effective() {
+        // Warming up from epoch 3, cooling down from epoch 8, against a cluster that
+        // only moves part of the change each epoch
+        let mut delegation = Delegation::new(&[1u8; 32], 1_000_000, 3u64.to_le_bytes());
+        delegation.deactivation_epoch = 8u64.to_le_bytes();
+        let entries: [(u64, StakeHistoryEntry); 12] = core::array::from_fn(|i| {
+            (i as u64, StakeHistoryEntry {
+                effective: 4_000_000u64.to_le_bytes(),
+                activating: 1_000_000u64.to_le_bytes(),
+                deactivating: 1_000_000u64.to_le_bytes(),
+            })
+        });
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        let stake = Stake { delegation, ..Stake::default() };
+
+        for rate_epoch in [None, Some([0u8; 8]), Some(6u64.to_le_bytes())] {
+            for epoch in 0..15u64 {
+                let status = delegation.stake_activating_and_deactivating(epoch.to_le_bytes(), &history, rate_epoch);
+                let effective = bytes_to_u64(status.effective);
+                assert_eq!(delegation.stake(epoch.to_le_bytes(), &history, rate_epoch), effective, "epoch {}", epoch);
+                assert_eq!(stake.stake(epoch.to_le_bytes(), &history, rate_epoch), effective, "epoch {}", epoch);
+            }
+        }
+    }
+
+    #[test]
+    fn deactivate_stores_epoch_le_bytes() {
+        let mut stake = Stake {
+            delegation: Delegation::new(&[1u8; 32], 1_000, 3u64.to_le_bytes()),
+            ..Stake::default()
+        };
+        let epoch = 0x0102_0304_0506_0708u64;
+        stake.deactivate(epoch).unwrap();
+        let stored = stake.delegation.deactivation_epoch;
+        assert_eq!(stored, [0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        assert!(matches!(stake.deactivate(epoch + 1), Err(StakeError::AlreadyDeactivated)));
+    }
+}
@@ -134,6 +134,40 @@ impl MergeKind {
         }
     }
 
+    /// Native classification, driven only by the stake history status with no
+    /// epoch fast paths: a stake still warming up or cooling down past its
+    /// activation/deactivation epoch is transient and rejected.
+    pub fn get_if_mergeable_from_history<T: StakeHistoryGetEntry>(
+        stake_state: &StakeStateV2,
+        stake_lamports: u64,
+        clock: &Clock,
+        stake_history: &T,
+    ) -> Result<Self, ProgramError> {
+        match stake_state {
+            StakeStateV2::Stake(meta, stake, flags) => {
+                let status = stake.delegation.stake_activating_and_deactivating(
+                    clock.epoch.to_le_bytes(),
+                    stake_history,
+                    crate::helpers::PERPETUAL_NEW_WARMUP_COOLDOWN_RATE_EPOCH,
+                );
+                match (
+                    bytes_to_u64(status.effective),
+                    bytes_to_u64(status.activating),
+                    bytes_to_u64(status.deactivating),
+                ) {
+                    (0, 0, 0) => Ok(Self::Inactive(*meta, stake_lamports, *flags)),
+                    (0, _, _) => Ok(Self::ActivationEpoch(*meta, *stake, *flags)),
+                    (_, 0, 0) => Ok(Self::FullyActive(*meta, *stake)),
+                    _ => Err(to_program_error(StakeError::MergeMismatch)),
+                }
+            }
+            StakeStateV2::Initialized(meta) => {
+                Ok(Self::Inactive(*meta, stake_lamports, StakeFlags::empty()))
+            }
+            _ => Err(to_program_error(StakeError::MergeMismatch)),
+        }
+    }
+
     /// Metadata compatibility check for merge
     pub fn metas_can_merge(dest: &Meta, source: &Meta, clock: &Clock) -> ProgramResult {
         // Authorities must match exactly
@@ -259,4 +293,127 @@ impl MergeKind {
 
         Ok(merged)
     }
-}   
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{delegation::Delegation, stake_history::StakeHistoryEntry};
+
+    const NO_HISTORY: &[(u64, StakeHistoryEntry)] = &[];
+
+    fn clock_at(epoch: u64) -> Clock {
+        Clock { slot: 0, epoch_start_timestamp: 0, epoch, leader_schedule_epoch: epoch, unix_timestamp: 0 }
+    }
+
+    fn deactivated_stake(stake: u64, activation_epoch: u64, deactivation_epoch: u64) -> StakeStateV2 {
+        let mut delegation = Delegation::new(&[7u8; 32], stake, activation_epoch.to_le_bytes());
+        delegation.deactivation_epoch = deactivation_epoch.to_le_bytes();
+        StakeStateV2::Stake(
+            Meta::default(),
+            DelegationStake { delegation, credits_observed: 0u64.to_le_bytes() },
+            StakeFlags::empty(),
+        )
+    }
+
+    #[test]
+    fn mid_cooldown_is_merge_mismatch() {
+        // Cluster cools 9% of 1_000 per epoch; our 1_000 is still deactivating one epoch later.
+        // Only the history-driven classification sees this; the epoch path calls it Inactive.
+        let entries = [(20, StakeHistoryEntry::with_deactivating(1_000))];
+        let state = deactivated_stake(1_000, 10, 20);
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        let res = MergeKind::get_if_mergeable_from_history(&state, 2_000, &clock_at(21), &history);
+        assert_eq!(res, Err(to_program_error(StakeError::MergeMismatch)));
+    }
+
+    #[test]
+    fn fully_cooled_down_is_inactive() {
+        // Large cluster effective stake drains our 1_000 within the first cooldown epoch
+        let entries = [(
+            20,
+            StakeHistoryEntry {
+                effective: 100_000u64.to_le_bytes(),
+                activating: [0u8; 8],
+                deactivating: 1_000u64.to_le_bytes(),
+            },
+        )];
+        let state = deactivated_stake(1_000, 10, 20);
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        let res = MergeKind::get_if_mergeable(&state, 2_000, &clock_at(40), &history);
+        assert!(matches!(res, Ok(MergeKind::Inactive(_, 2_000, _))), "got {:?}", res);
+    }
+
+    #[test]
+    fn deactivation_epoch_itself_is_merge_mismatch() {
+        let state = deactivated_stake(1_000, 10, 20);
+        let res = MergeKind::get_if_mergeable(&state, 2_000, &clock_at(20), &NO_HISTORY);
+        assert_eq!(res, Err(to_program_error(StakeError::MergeMismatch)));
+    }
+
+    #[test]
+    fn initialized_is_inactive_with_all_lamports() {
+        let state = StakeStateV2::Initialized(Meta::default());
+        let res = MergeKind::get_if_mergeable(&state, 5_000, &clock_at(3), &NO_HISTORY);
+        assert_eq!(res, Ok(MergeKind::Inactive(Meta::default(), 5_000, StakeFlags::empty())));
+    }
+
+    #[test]
+    fn activation_epoch_is_transient() {
+        let state = deactivated_stake(1_000, 10, u64::MAX);
+        let res = MergeKind::get_if_mergeable(&state, 2_000, &clock_at(10), &NO_HISTORY);
+        assert!(matches!(res, Ok(MergeKind::ActivationEpoch(..))), "got {:?}", res);
+    }
+
+    #[test]
+    fn past_activation_is_fully_active() {
+        let entries = [(10, StakeHistoryEntry::with_effective_and_activating(100_000, 1_000))];
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        let state = deactivated_stake(1_000, 10, u64::MAX);
+        let StakeStateV2::Stake(_, stake, _) = state else { unreachable!() };
+        let res = MergeKind::get_if_mergeable(&state, 2_000, &clock_at(11), &history);
+        assert_eq!(res, Ok(MergeKind::FullyActive(Meta::default(), stake)));
+    }
+
+    #[test]
+    fn partial_warmup_is_transient_from_history() {
+        // Cluster warms 9% of 100_000 against 10_000 activating: ~900 of our 1_000 is effective
+        let entries = [(10, StakeHistoryEntry::with_effective_and_activating(100_000, 10_000))];
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        let state = deactivated_stake(1_000, 10, u64::MAX);
+
+        let strict = MergeKind::get_if_mergeable_from_history(&state, 2_000, &clock_at(11), &history);
+        assert_eq!(strict, Err(to_program_error(StakeError::MergeMismatch)));
+        // The epoch fast path reports the same account as fully active
+        let fast = MergeKind::get_if_mergeable(&state, 2_000, &clock_at(11), &history);
+        assert!(matches!(fast, Ok(MergeKind::FullyActive(..))), "got {:?}", fast);
+    }
+
+    #[test]
+    fn history_classification_matches_native_for_settled_shapes() {
+        let entries = [(10, StakeHistoryEntry::with_effective_and_activating(100_000, 1_000))];
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        let active = deactivated_stake(1_000, 10, u64::MAX);
+        let res = MergeKind::get_if_mergeable_from_history(&active, 2_000, &clock_at(11), &history);
+        assert!(matches!(res, Ok(MergeKind::FullyActive(..))), "got {:?}", res);
+
+        let res = MergeKind::get_if_mergeable_from_history(&active, 2_000, &clock_at(10), &NO_HISTORY);
+        assert!(matches!(res, Ok(MergeKind::ActivationEpoch(..))), "got {:?}", res);
+
+        let res = MergeKind::get_if_mergeable_from_history(
+            &StakeStateV2::Initialized(Meta::default()),
+            5_000,
+            &clock_at(3),
+            &NO_HISTORY,
+        );
+        assert_eq!(res, Ok(MergeKind::Inactive(Meta::default(), 5_000, StakeFlags::empty())));
+    }
+
+    #[test]
+    fn uninitialized_and_rewards_pool_are_merge_mismatch() {
+        for state in [StakeStateV2::Uninitialized, StakeStateV2::RewardsPool] {
+            let res = MergeKind::get_if_mergeable(&state, 1_000, &clock_at(1), &NO_HISTORY);
+            assert_eq!(res, Err(to_program_error(StakeError::MergeMismatch)));
+        }
+    }
+}
@@ -4,6 +4,10 @@
 // When the feature `enforce-stake-config` is enabled, handlers may verify
 // the 5th account matches this pubkey. The ID matches Solana's native
 // stake-config program id for shape parity purposes.
+//
+// The account's `warmup_cooldown_rate` is deliberately not read: native stake
+// takes the rate from the fixed schedule (`helpers::warmup_cooldown_rate`), so a
+// config-driven rate would compute effective stake differently from native.
 
 use pinocchio_pubkey::declare_id;
 
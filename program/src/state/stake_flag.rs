@@ -30,6 +30,18 @@ impl StakeFlags {
             bits: self.bits | other.bits,
         }
     }
+
+    pub const fn intersect(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+
+    pub const fn difference(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & !other.bits,
+        }
+    }
 }
 
 impl Default for StakeFlags {
@@ -37,3 +49,25 @@ impl Default for StakeFlags {
         StakeFlags::empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MUST: StakeFlags = StakeFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED;
+    const RESERVED: StakeFlags = StakeFlags { bits: 0b1000_0000 };
+
+    #[test]
+    fn set_operations() {
+        let both = MUST.union(RESERVED);
+        assert_eq!(both.bits, 0b1000_0001);
+        assert_eq!(StakeFlags::empty().union(MUST), MUST);
+
+        assert_eq!(both.intersect(MUST), MUST);
+        assert_eq!(MUST.intersect(RESERVED), StakeFlags::empty());
+
+        assert_eq!(both.difference(MUST), RESERVED);
+        assert_eq!(MUST.difference(MUST), StakeFlags::empty());
+        assert_eq!(MUST.difference(RESERVED), MUST);
+    }
+}
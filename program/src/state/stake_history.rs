@@ -105,13 +105,15 @@ impl StakeHistory {
         }
         let mut len_bytes = [0u8; 8];
         len_bytes.copy_from_slice(&data[..8]);
-        let len = u64::from_le_bytes(len_bytes) as usize;
-        let want = len.saturating_mul(EPOCH_AND_ENTRY_SERIALIZED_SIZE as usize)
-            .saturating_add(core::mem::size_of::<u64>());
+        let declared = u64::from_le_bytes(len_bytes);
+        // `declared` is untrusted: clamp to the sysvar's capacity before any
+        // size math, then only require bytes for the entries actually read.
+        // A buffer too short for those entries yields an empty history.
+        let take = core::cmp::min(declared, MAX_STAKE_HISTORY_ENTRIES as u64) as usize;
+        let want = take * EPOCH_AND_ENTRY_SERIALIZED_SIZE as usize + core::mem::size_of::<u64>();
         if data.len() < want { return sh; }
 
         let mut off = 8usize; // skip len
-        let take = core::cmp::min(len, MAX_STAKE_HISTORY_ENTRIES);
         for _ in 0..take {
             let epoch = u64::from_le_bytes(data[off..off + 8].try_into().unwrap());
             let effective = u64::from_le_bytes(data[off + 8..off + 16].try_into().unwrap());
@@ -146,53 +148,233 @@ impl StakeHistory {
 }
 const EPOCH_AND_ENTRY_SERIALIZED_SIZE: u64 = 32;
 
+/// Byte offset of `target_epoch`'s record in a history holding `len` entries
+/// as of `current_epoch`; `None` for the current/future epochs or epochs older
+/// than the buffer covers.
+fn entry_offset(current_epoch: Epoch, len: u64, target_epoch: Epoch) -> Option<u64> {
+    // Cannot query current or future epoch
+    let newest_historical_epoch = current_epoch.checked_sub(1)?;
+    if target_epoch > newest_historical_epoch { return None; }
+
+    // Records are newest-first, as the runtime writes them: index 0 holds
+    // current_epoch-1, index i holds current_epoch-1-i
+    let idx = newest_historical_epoch.checked_sub(target_epoch)?;
+    if idx >= len { return None; }
+
+    // Compute byte offset: skip len (8) + idx * entry_size
+    8u64.checked_add(idx.checked_mul(EPOCH_AND_ENTRY_SERIALIZED_SIZE)?)
+}
+
+/// Decode one serialized `(epoch, entry)` record, rejecting an epoch mismatch.
+fn decode_entry(entry_buf: &[u8], target_epoch: Epoch) -> Option<StakeHistoryEntry> {
+    let entry_epoch = u64::from_le_bytes(entry_buf[0..8].try_into().unwrap());
+    let effective = u64::from_le_bytes(entry_buf[8..16].try_into().unwrap());
+    let activating = u64::from_le_bytes(entry_buf[16..24].try_into().unwrap());
+    let deactivating = u64::from_le_bytes(entry_buf[24..32].try_into().unwrap());
+
+    // Verify epoch matches target; if not, return None (layout mismatch or gap)
+    if entry_epoch != target_epoch { return None; }
+
+    Some(StakeHistoryEntry {
+        effective: effective.to_le_bytes(),
+        activating: activating.to_le_bytes(),
+        deactivating: deactivating.to_le_bytes(),
+    })
+}
+
 impl StakeHistoryGetEntry for StakeHistorySysvar {
     fn get_entry(&self, target_epoch: Epoch) -> Option<StakeHistoryEntry> {
         let current_epoch = self.0;
-
-        // Cannot query current or future epoch
-        let newest_historical_epoch = current_epoch.checked_sub(1)?;
-        if target_epoch > newest_historical_epoch { return None; }
+        if target_epoch >= current_epoch { return None; }
 
         // Read vector length
         let mut len_buf = [0u8; 8];
         if get_sysvar(&mut len_buf, &ID, 0, 8).is_err() { return None; }
         let len = u64::from_le_bytes(len_buf);
-        if len == 0 { return None; }
-
-        // Oldest epoch present in the sysvar buffer
-        // Oldest = current_epoch - len (saturating)
-        let oldest_historical_epoch = current_epoch.saturating_sub(len);
-        if target_epoch < oldest_historical_epoch { return None; }
-
-        // Index of target within the vector (0-based from start of entries)
-        // newest index = len-1 corresponds to epoch = current_epoch-1
-        // idx = (target_epoch - oldest_historical_epoch)
-        let distance_from_oldest = target_epoch.checked_sub(oldest_historical_epoch)?;
-        if distance_from_oldest >= len { return None; }
-        let idx = distance_from_oldest;
-
-        // Compute byte offset: skip len (8) + idx * entry_size
-        let offset = 8u64
-            .checked_add(idx.checked_mul(EPOCH_AND_ENTRY_SERIALIZED_SIZE)?)?;
 
+        let offset = entry_offset(current_epoch, len, target_epoch)?;
         let mut entry_buf = [0u8; EPOCH_AND_ENTRY_SERIALIZED_SIZE as usize];
         if get_sysvar(&mut entry_buf, &ID, offset, EPOCH_AND_ENTRY_SERIALIZED_SIZE).is_err() {
             return None;
         }
+        decode_entry(&entry_buf, target_epoch)
+    }
+}
+
+/// Stake history over preloaded sysvar bytes (same layout and lookup as
+/// `StakeHistorySysvar`), for use without the `sol_get_sysvar` syscall.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StakeHistoryData<'a> {
+    pub epoch: Epoch,
+    pub bytes: &'a [u8],
+}
 
-        let entry_epoch = u64::from_le_bytes(entry_buf[0..8].try_into().unwrap());
-        let effective = u64::from_le_bytes(entry_buf[8..16].try_into().unwrap());
-        let activating = u64::from_le_bytes(entry_buf[16..24].try_into().unwrap());
-        let deactivating = u64::from_le_bytes(entry_buf[24..32].try_into().unwrap());
+impl StakeHistoryGetEntry for StakeHistoryData<'_> {
+    fn get_entry(&self, target_epoch: Epoch) -> Option<StakeHistoryEntry> {
+        let len = u64::from_le_bytes(self.bytes.get(..8)?.try_into().unwrap());
+        let offset = usize::try_from(entry_offset(self.epoch, len, target_epoch)?).ok()?;
+        let end = offset.checked_add(EPOCH_AND_ENTRY_SERIALIZED_SIZE as usize)?;
+        decode_entry(self.bytes.get(offset..end)?, target_epoch)
+    }
+}
 
-        // Verify epoch matches target; if not, return None (layout mismatch or gap)
-        if entry_epoch != target_epoch { return None; }
+/// In-memory `(epoch, entry)` pairs in any order, e.g. deterministic histories
+/// for unit tests. Lookup is linear; the first matching epoch wins.
+impl StakeHistoryGetEntry for &[(Epoch, StakeHistoryEntry)] {
+    fn get_entry(&self, target_epoch: Epoch) -> Option<StakeHistoryEntry> {
+        self.iter().find(|(epoch, _)| *epoch == target_epoch).map(|(_, entry)| entry.clone())
+    }
+}
 
-        Some(StakeHistoryEntry {
-            effective: effective.to_le_bytes(),
-            activating: activating.to_le_bytes(),
-            deactivating: deactivating.to_le_bytes(),
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_bytes(declared: u64, entries: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&declared.to_le_bytes());
+        for i in 0..entries {
+            out.extend_from_slice(&(entries - i).to_le_bytes()); // epoch, newest first
+            out.extend_from_slice(&i.to_le_bytes());
+            out.extend_from_slice(&(i * 2).to_le_bytes());
+            out.extend_from_slice(&(i * 3).to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn absurd_declared_len_is_clamped() {
+        let full = MAX_STAKE_HISTORY_ENTRIES as u64;
+        let sh = StakeHistory::from_account_data(&history_bytes(u64::MAX, full), 0);
+        assert_eq!(sh.len, MAX_STAKE_HISTORY_ENTRIES);
+        assert_eq!(sh.get(1).unwrap().activating, 2u64.to_le_bytes());
+
+        // Clamped count still has to fit in the buffer
+        let sh = StakeHistory::from_account_data(&history_bytes(u64::MAX, 3), 0);
+        assert_eq!(sh.len, 0);
+    }
+
+    #[test]
+    fn large_well_formed_buffer_reads_capacity() {
+        let sh = StakeHistory::from_account_data(&history_bytes(600, 600), 0);
+        assert_eq!(sh.len, MAX_STAKE_HISTORY_ENTRIES);
+        let last = sh.get(MAX_STAKE_HISTORY_ENTRIES - 1).unwrap();
+        assert_eq!(last.effective, 511u64.to_le_bytes());
+        assert_eq!(last.deactivating, (511u64 * 3).to_le_bytes());
+    }
+
+    // Newest-first records for epochs [current - n, current - 1], as the runtime lays them out
+    fn sysvar_bytes(current_epoch: u64, n: u64) -> Vec<u8> {
+        let mut out = n.to_le_bytes().to_vec();
+        for epoch in (current_epoch - n..current_epoch).rev() {
+            out.extend_from_slice(&epoch.to_le_bytes());
+            out.extend_from_slice(&(epoch * 10).to_le_bytes());
+            out.extend_from_slice(&(epoch * 20).to_le_bytes());
+            out.extend_from_slice(&(epoch * 30).to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn in_memory_get_entry_bounds() {
+        let bytes = sysvar_bytes(100, 5);
+        let history = StakeHistoryData { epoch: 100, bytes: &bytes };
+
+        // Newest and oldest recorded epochs
+        assert_eq!(
+            history.get_entry(99),
+            Some(StakeHistoryEntry {
+                effective: 990u64.to_le_bytes(),
+                activating: 1_980u64.to_le_bytes(),
+                deactivating: 2_970u64.to_le_bytes(),
+            })
+        );
+        assert_eq!(history.get_entry(95).unwrap().effective, 950u64.to_le_bytes());
+
+        // Current, future, and older than the buffer
+        assert_eq!(history.get_entry(100), None);
+        assert_eq!(history.get_entry(101), None);
+        assert_eq!(history.get_entry(94), None);
+
+        // Truncated or empty buffers never panic
+        // Newest-first: a short first record hides epoch 99, a short tail hides epoch 95
+        assert_eq!(StakeHistoryData { epoch: 100, bytes: &bytes[..8 + 31] }.get_entry(99), None);
+        assert_eq!(StakeHistoryData { epoch: 100, bytes: &bytes[..bytes.len() - 1] }.get_entry(95), None);
+        assert_eq!(StakeHistoryData { epoch: 100, bytes: &[] }.get_entry(99), None);
+        assert_eq!(StakeHistoryData { epoch: 0, bytes: &bytes }.get_entry(0), None);
+    }
+
+    #[test]
+    fn reads_runtime_serialized_history() {
+        let mut native = solana_sdk::stake_history::StakeHistory::default();
+        for epoch in 0..10u64 {
+            native.add(epoch, solana_sdk::stake_history::StakeHistoryEntry {
+                effective: epoch * 100,
+                activating: epoch * 10,
+                deactivating: epoch,
+            });
+        }
+        let bytes = bincode::serialize(&native).unwrap();
+        let history = StakeHistoryData { epoch: 10, bytes: &bytes };
+        for epoch in 0..10u64 {
+            let expected = native.get(epoch).unwrap();
+            let entry = history.get_entry(epoch).unwrap();
+            assert_eq!(u64::from_le_bytes(entry.effective), expected.effective);
+            assert_eq!(u64::from_le_bytes(entry.activating), expected.activating);
+            assert_eq!(u64::from_le_bytes(entry.deactivating), expected.deactivating);
+        }
+        assert_eq!(history.get_entry(10), None);
+    }
+
+    #[test]
+    fn gap_in_history_reads_as_missing_not_misattributed() {
+        // Epochs 99..=94 with 97 missing: the records after the gap shift up a slot
+        let full = sysvar_bytes(100, 6);
+        let mut bytes = 5u64.to_le_bytes().to_vec();
+        for record in full[8..].chunks(32) {
+            if record[..8] != 97u64.to_le_bytes() {
+                bytes.extend_from_slice(record);
+            }
+        }
+        let history = StakeHistoryData { epoch: 100, bytes: &bytes };
+
+        assert_eq!(history.get_entry(97), None);
+        assert_eq!(history.get_entry(99).unwrap().effective, 990u64.to_le_bytes());
+        assert_eq!(history.get_entry(98).unwrap().effective, 980u64.to_le_bytes());
+        // Older epochs land on a neighbour's record; the embedded epoch rejects it
+        for epoch in 94..97 {
+            assert_eq!(history.get_entry(epoch), None, "epoch {}", epoch);
+        }
+
+        // Activation math over the gap treats missing entries as no data and never panics
+        let stake = 1_000_000u64;
+        for (activation, deactivation) in [(94u64, u64::MAX), (96, 98), (97, 99), (98, u64::MAX)] {
+            let mut delegation = crate::state::delegation::Delegation::new(&[1u8; 32], stake, activation.to_le_bytes());
+            delegation.deactivation_epoch = deactivation.to_le_bytes();
+            for target in 90u64..=101 {
+                let status = delegation.stake_activating_and_deactivating(target.to_le_bytes(), &history, None);
+                assert!(u64::from_le_bytes(status.effective) <= stake, "target {}", target);
+            }
+        }
+    }
+
+    #[test]
+    fn slice_history_looks_up_by_epoch() {
+        let entries = [
+            (7, StakeHistoryEntry::with_effective(700)),
+            (5, StakeHistoryEntry::with_deactivating(50)),
+        ];
+        let history: &[(u64, StakeHistoryEntry)] = &entries;
+        assert_eq!(history.get_entry(7), Some(StakeHistoryEntry::with_effective(700)));
+        assert_eq!(history.get_entry(5), Some(StakeHistoryEntry::with_deactivating(50)));
+        assert_eq!(history.get_entry(6), None);
+    }
+
+    #[test]
+    fn buffer_short_of_declared_len_is_empty() {
+        let mut bytes = history_bytes(3, 3);
+        bytes.truncate(bytes.len() - 1);
+        assert_eq!(StakeHistory::from_account_data(&bytes, 0).len, 0);
+        assert_eq!(StakeHistory::from_account_data(&history_bytes(3, 3), 0).len, 3);
     }
 }
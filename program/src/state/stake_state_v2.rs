@@ -22,6 +22,21 @@ impl StakeStateV2 {
     /// The fixed number of bytes used to serialize each stake account
     pub const fn size_of() -> usize { Self::ACCOUNT_SIZE }
 
+    /// Build a `Stake` variant, rejecting zero delegated lamports. Used where a
+    /// stake is created or delegated, not on every write: legacy zero-delegation
+    /// accounts must stay usable by Authorize, SetLockup and Deactivate, as natively.
+    pub fn new_stake(meta: Meta, stake: Stake, flags: StakeFlags) -> Result<Self, ProgramError> {
+        if u64::from_le_bytes(stake.delegation.stake) == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(StakeStateV2::Stake(meta, stake, flags))
+    }
+
+    /// No alignment check: the runtime hands out 8-byte aligned account data,
+    /// and fields are read with `read_unaligned`, so arbitrary slices (tests,
+    /// client buffers) decode as well. Decoding copies the fixed-size fields out,
+    /// so a borrowed `Ref<[u8]>` from `try_borrow_data` can be passed directly and
+    /// released as soon as this returns.
     pub fn deserialize(data: &[u8]) -> Result<Self, ProgramError> {
         if data.is_empty() {
             return Err(ProgramError::InvalidAccountData);
@@ -127,6 +142,45 @@ impl StakeStateV2 {
     // helpers::get_stake_state()/set_stake_state() for safe (de)serialization.
 }
 
+/// Mutable borrow of a stake account's data for single-field updates that
+/// would otherwise re-serialize the whole state. Obtain via
+/// `helpers::get_stake_state_mut`.
+pub struct StakeStateMut<'a> {
+    data: AiRefMut<'a, [u8]>,
+}
+
+impl<'a> StakeStateMut<'a> {
+    const DEACTIVATION_EPOCH_OFFSET: usize = 1
+        + core::mem::size_of::<Meta>()
+        + core::mem::offset_of!(Stake, delegation)
+        + core::mem::offset_of!(crate::state::delegation::Delegation, deactivation_epoch);
+
+    pub fn from_account_info(stake_account_info: &'a AccountInfo) -> Result<Self, ProgramError> {
+        if *stake_account_info.owner() != ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if stake_account_info.key() == &ID {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Self { data: stake_account_info.try_borrow_mut_data()? })
+    }
+
+    /// Decode the current state (a copy; later writes through `self` do not update it).
+    pub fn state(&self) -> Result<StakeStateV2, ProgramError> {
+        StakeStateV2::deserialize(&self.data)
+    }
+
+    /// Overwrite `delegation.deactivation_epoch` only; the account must hold a `Stake`.
+    pub fn set_deactivation_epoch(&mut self, epoch: u64) -> Result<(), ProgramError> {
+        let off = Self::DEACTIVATION_EPOCH_OFFSET;
+        if self.data.first() != Some(&2) || self.data.len() < off + 8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.data[off..off + 8].copy_from_slice(&epoch.to_le_bytes());
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // use pinocchio::msg;
@@ -147,4 +201,102 @@ mod tests {
         log!("Meta size: {} Stake size: {} flags_off: {}", m, s, flags_offset);
         assert!(flags_offset < StakeStateV2::ACCOUNT_SIZE);
     }
+
+    #[test]
+    fn new_stake_rejects_zero_delegation() {
+        let mut stake = Stake::default();
+        assert_eq!(
+            StakeStateV2::new_stake(Meta::default(), stake, StakeFlags::empty()),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        stake.delegation.stake = 1u64.to_le_bytes();
+        let flags = StakeFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED;
+        assert_eq!(
+            StakeStateV2::new_stake(Meta::default(), stake, flags),
+            Ok(StakeStateV2::Stake(Meta::default(), stake, flags))
+        );
+    }
+
+    #[test]
+    fn deserialize_aligned_and_unaligned_buffers() {
+        let mut stake = Stake::default();
+        stake.delegation.stake = 42u64.to_le_bytes();
+        let state = StakeStateV2::Stake(Meta::default(), stake, StakeFlags::empty());
+
+        // u64-backed buffer, aligned the way the runtime lays out account data
+        let mut words = [0u64; StakeStateV2::ACCOUNT_SIZE / 8];
+        let aligned = unsafe {
+            core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, StakeStateV2::ACCOUNT_SIZE)
+        };
+        assert_eq!(aligned.as_ptr() as usize % 8, 0);
+        state.serialize(aligned).unwrap();
+        assert_eq!(StakeStateV2::deserialize(aligned).unwrap(), state);
+
+        // Same bytes at an odd offset still decode
+        let mut shifted = [0u8; StakeStateV2::ACCOUNT_SIZE + 1];
+        shifted[1..].copy_from_slice(aligned);
+        assert_eq!(StakeStateV2::deserialize(&shifted[1..]).unwrap(), state);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn warmup_cooldown_rate_round_trips() {
+        let rate = 0.09f64;
+        let mut stake = Stake::default();
+        stake.delegation.stake = 7u64.to_le_bytes();
+        stake.delegation.warmup_cooldown_rate = rate.to_bits().to_le_bytes();
+        let state = StakeStateV2::Stake(Meta::default(), stake, StakeFlags::empty());
+
+        let mut data = [0u8; StakeStateV2::ACCOUNT_SIZE];
+        state.serialize(&mut data).unwrap();
+
+        // Field sits right after the delegation epochs, as f64 bits LE
+        let off = 1
+            + core::mem::size_of::<Meta>()
+            + core::mem::offset_of!(Stake, delegation)
+            + core::mem::offset_of!(crate::state::delegation::Delegation, warmup_cooldown_rate);
+        assert_eq!(off, 1 + 120 + 32 + 8 + 8 + 8);
+        assert_eq!(data[off..off + 8], rate.to_bits().to_le_bytes());
+
+        let StakeStateV2::Stake(_, decoded, _) = StakeStateV2::deserialize(&data).unwrap() else {
+            panic!("expected Stake");
+        };
+        let decoded_rate = f64::from_bits(u64::from_le_bytes(decoded.delegation.warmup_cooldown_rate));
+        assert_eq!(decoded_rate, rate);
+    }
+
+    #[test]
+    fn negative_lockup_timestamp_round_trips() {
+        let lockup = crate::state::state::Lockup { unix_timestamp: -1_700_000_000, epoch: u64::MAX - 1, custodian: [9u8; 32] };
+        let meta = Meta { lockup, ..Meta::default() };
+        let state = StakeStateV2::Initialized(meta);
+
+        let mut data = [0u8; StakeStateV2::ACCOUNT_SIZE];
+        state.serialize(&mut data).unwrap();
+
+        // Signed value is stored as two's-complement i64 LE, right after reserve + authorities
+        let off = 1 + core::mem::offset_of!(Meta, lockup);
+        assert_eq!(off, 1 + 8 + 64);
+        assert_eq!(data[off..off + 8], (-1_700_000_000i64).to_le_bytes());
+        assert_eq!(data[off + 8..off + 16], (u64::MAX - 1).to_le_bytes());
+        assert_eq!(StakeStateV2::deserialize(&data).unwrap(), state);
+    }
+
+    #[test]
+    fn rewards_pool_round_trips() {
+        // Stale bytes from a previous Stake are cleared; only the tag remains
+        let mut data = [0xAAu8; StakeStateV2::ACCOUNT_SIZE];
+        StakeStateV2::RewardsPool.serialize(&mut data).unwrap();
+        assert_eq!(data[0], 3);
+        assert!(data[1..].iter().all(|b| *b == 0));
+        assert_eq!(StakeStateV2::deserialize(&data).unwrap(), StakeStateV2::RewardsPool);
+
+        // Short buffers are refused rather than partially written
+        let mut short = [0u8; StakeStateV2::ACCOUNT_SIZE - 1];
+        assert_eq!(
+            StakeStateV2::RewardsPool.serialize(&mut short),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+    }
 }
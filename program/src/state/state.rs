@@ -32,6 +32,14 @@ impl Meta {
         core::mem::size_of::<Meta>()
     }
 
+    pub fn new(rent_exempt_reserve: u64, authorized: Authorized, lockup: Lockup) -> Self {
+        Self {
+            rent_exempt_reserve: rent_exempt_reserve.to_le_bytes(),
+            authorized,
+            lockup,
+        }
+    }
+
     /// SAFETY: This function performs an unchecked shared borrow of account
     /// data and casts it to `Meta`. Callers must ensure no active mutable
     /// borrows exist and uphold aliasing guarantees while the reference lives.
@@ -132,3 +140,25 @@ impl Lockup {
         time_in_force || epoch_in_force
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meta_new_byte_layout() {
+        let lockup = Lockup { unix_timestamp: -5, epoch: 7, custodian: [3u8; 32] };
+        let meta = Meta::new(2_282_880, Authorized::new([1u8; 32], [2u8; 32]), lockup);
+
+        let bytes = unsafe {
+            core::slice::from_raw_parts(&meta as *const Meta as *const u8, Meta::size())
+        };
+        assert_eq!(bytes.len(), 8 + 64 + 48);
+        assert_eq!(&bytes[0..8], &2_282_880u64.to_le_bytes());
+        assert_eq!(&bytes[8..40], &[1u8; 32]);
+        assert_eq!(&bytes[40..72], &[2u8; 32]);
+        assert_eq!(&bytes[72..80], &(-5i64).to_le_bytes());
+        assert_eq!(&bytes[80..88], &7u64.to_le_bytes());
+        assert_eq!(&bytes[88..120], &[3u8; 32]);
+    }
+}
@@ -0,0 +1,255 @@
+// Wire decoding for StakeInstruction (bincode) for host/dev (std)
+
+use serde::{Deserialize, Serialize};
+
+pub type WirePubkey = [u8; 32];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Authorized { pub staker: WirePubkey, pub withdrawer: WirePubkey }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockup { pub unix_timestamp: i64, pub epoch: u64, pub custodian: WirePubkey }
+
+impl From<Authorized> for crate::state::accounts::Authorized {
+    fn from(a: Authorized) -> Self { Self::new(a.staker, a.withdrawer) }
+}
+impl From<Lockup> for crate::state::state::Lockup {
+    fn from(l: Lockup) -> Self { Self { unix_timestamp: l.unix_timestamp, epoch: l.epoch, custodian: l.custodian } }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StakeAuthorize { Staker, Withdrawer }
+
+impl From<StakeAuthorize> for crate::state::StakeAuthorize {
+    fn from(a: StakeAuthorize) -> Self {
+        match a { StakeAuthorize::Staker => Self::Staker, StakeAuthorize::Withdrawer => Self::Withdrawer }
+    }
+}
+impl From<crate::state::StakeAuthorize> for StakeAuthorize {
+    fn from(a: crate::state::StakeAuthorize) -> Self {
+        match a { crate::state::StakeAuthorize::Staker => Self::Staker, crate::state::StakeAuthorize::Withdrawer => Self::Withdrawer }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockupArgs { pub unix_timestamp: Option<i64>, pub epoch: Option<u64>, pub custodian: Option<WirePubkey> }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockupCheckedArgs { pub unix_timestamp: Option<i64>, pub epoch: Option<u64> }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizeWithSeedArgs { pub new_authorized_pubkey: WirePubkey, pub stake_authorize: StakeAuthorize, pub authority_seed: String, pub authority_owner: WirePubkey }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizeCheckedWithSeedArgs { pub stake_authorize: StakeAuthorize, pub authority_seed: String, pub authority_owner: WirePubkey }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StakeInstruction {
+    Initialize(Authorized, Lockup),
+    Authorize(WirePubkey, StakeAuthorize),
+    DelegateStake,
+    Split(u64),
+    Withdraw(u64),
+    Deactivate,
+    SetLockup(LockupArgs),
+    Merge,
+    AuthorizeWithSeed(AuthorizeWithSeedArgs),
+    InitializeChecked,
+    AuthorizeChecked(StakeAuthorize),
+    AuthorizeCheckedWithSeed(AuthorizeCheckedWithSeedArgs),
+    SetLockupChecked(LockupCheckedArgs),
+    GetMinimumDelegation,
+    DeactivateDelinquent,
+    // Deprecated natively; rejected at dispatch
+    Redelegate,
+    MoveStake(u64),
+    MoveLamports(u64),
+}
+
+#[cfg(test)]
+#[allow(deprecated)] // the SDK's stake instruction builders
+mod tests {
+    //! Round-trip guard: every native builder's bytes must decode through both
+    //! wire decoders (bincode here, the manual SBF reader in `wire_sbf`) into
+    //! the same variant and fields the SDK itself decodes.
+    use solana_sdk::{
+        instruction::Instruction as SdkInstruction,
+        pubkey::Pubkey as SdkPubkey,
+        stake::{
+            instruction::{self as sdk, LockupArgs, LockupCheckedArgs, StakeInstruction as SdkIx},
+            state::{Authorized, Lockup, StakeAuthorize as SdkStakeAuthorize},
+        },
+    };
+
+    use crate::state::{accounts::Authorized as StateAuthorized, state::Lockup as StateLockup};
+
+    const ROUNDS: usize = 64;
+
+    // Deterministic xorshift so failures are reproducible without a proptest dependency
+    struct Rng(u64);
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            let mut x = self.0;
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            self.0 = x;
+            x
+        }
+        fn pubkey(&mut self) -> SdkPubkey {
+            let mut b = [0u8; 32];
+            for chunk in b.chunks_mut(8) { chunk.copy_from_slice(&self.next().to_le_bytes()); }
+            SdkPubkey::new_from_array(b)
+        }
+        fn authorize(&mut self) -> SdkStakeAuthorize {
+            if self.next() & 1 == 0 { SdkStakeAuthorize::Staker } else { SdkStakeAuthorize::Withdrawer }
+        }
+        fn opt_i64(&mut self) -> Option<i64> { if self.next() & 1 == 0 { None } else { Some(self.next() as i64) } }
+        fn opt_u64(&mut self) -> Option<u64> { if self.next() & 1 == 0 { None } else { Some(self.next()) } }
+        fn seed(&mut self) -> String {
+            let len = (self.next() % 33) as usize;
+            (0..len).map(|_| (b'a' + (self.next() % 26) as u8) as char).collect()
+        }
+    }
+
+    fn stake_ix(ixs: Vec<SdkInstruction>) -> SdkInstruction {
+        ixs.into_iter()
+            .rev()
+            .find(|ix| ix.program_id == solana_sdk::stake::program::id())
+            .expect("builder emits a stake instruction")
+    }
+
+    /// One instruction per native variant, built through the SDK helpers.
+    fn sdk_cases(rng: &mut Rng) -> Vec<SdkInstruction> {
+        let (stake, other, auth, new_auth) = (rng.pubkey(), rng.pubkey(), rng.pubkey(), rng.pubkey());
+        let custodian = rng.pubkey();
+        let lockup_args = LockupArgs { unix_timestamp: rng.opt_i64(), epoch: rng.opt_u64(), custodian: if rng.next() & 1 == 0 { None } else { Some(custodian) } };
+        let checked_args = LockupArgs { unix_timestamp: rng.opt_i64(), epoch: rng.opt_u64(), custodian: None };
+        #[allow(deprecated)]
+        let redelegate = SdkInstruction::new_with_bincode(solana_sdk::stake::program::id(), &SdkIx::Redelegate, vec![]);
+        vec![
+            sdk::initialize(
+                &stake,
+                &Authorized { staker: auth, withdrawer: new_auth },
+                &Lockup { unix_timestamp: rng.next() as i64, epoch: rng.next(), custodian },
+            ),
+            sdk::authorize(&stake, &auth, &new_auth, rng.authorize(), None),
+            sdk::delegate_stake(&stake, &auth, &other),
+            stake_ix(sdk::split(&stake, &auth, rng.next(), &other)),
+            sdk::withdraw(&stake, &auth, &other, rng.next(), None),
+            sdk::deactivate_stake(&stake, &auth),
+            sdk::set_lockup(&stake, &lockup_args, &auth),
+            stake_ix(sdk::merge(&stake, &other, &auth)),
+            sdk::authorize_with_seed(&stake, &auth, rng.seed(), &other, &new_auth, rng.authorize(), None),
+            sdk::initialize_checked(&stake, &Authorized { staker: auth, withdrawer: new_auth }),
+            sdk::authorize_checked(&stake, &auth, &new_auth, rng.authorize(), None),
+            sdk::authorize_checked_with_seed(&stake, &auth, rng.seed(), &other, &new_auth, rng.authorize(), None),
+            sdk::set_lockup_checked(&stake, &checked_args, &auth),
+            sdk::get_minimum_delegation(),
+            sdk::deactivate_delinquent_stake(&stake, &other, &new_auth),
+            redelegate,
+            sdk::move_stake(&stake, &other, &auth, rng.next()),
+            sdk::move_lamports(&stake, &other, &auth, rng.next()),
+        ]
+    }
+
+    fn pk(w: [u8; 32]) -> SdkPubkey { SdkPubkey::new_from_array(w) }
+
+    macro_rules! wire_to_sdk {
+        ($m:ident, $ix:expr, $seed:expr) => {{
+            use crate::$m::{StakeAuthorize as WA, StakeInstruction as W};
+            let auth = |a: WA| match a { WA::Staker => SdkStakeAuthorize::Staker, WA::Withdrawer => SdkStakeAuthorize::Withdrawer };
+            #[allow(deprecated)]
+            match $ix {
+                W::Initialize(a, l) => SdkIx::Initialize(
+                    Authorized { staker: pk(a.staker), withdrawer: pk(a.withdrawer) },
+                    Lockup { unix_timestamp: l.unix_timestamp, epoch: l.epoch, custodian: pk(l.custodian) },
+                ),
+                W::Authorize(k, a) => SdkIx::Authorize(pk(k), auth(a)),
+                W::DelegateStake => SdkIx::DelegateStake,
+                W::Split(l) => SdkIx::Split(l),
+                W::Withdraw(l) => SdkIx::Withdraw(l),
+                W::Deactivate => SdkIx::Deactivate,
+                W::SetLockup(a) => SdkIx::SetLockup(LockupArgs { unix_timestamp: a.unix_timestamp, epoch: a.epoch, custodian: a.custodian.map(pk) }),
+                W::Merge => SdkIx::Merge,
+                W::AuthorizeWithSeed(a) => SdkIx::AuthorizeWithSeed(sdk::AuthorizeWithSeedArgs {
+                    new_authorized_pubkey: pk(a.new_authorized_pubkey),
+                    stake_authorize: auth(a.stake_authorize),
+                    authority_seed: $seed(a.authority_seed),
+                    authority_owner: pk(a.authority_owner),
+                }),
+                W::InitializeChecked => SdkIx::InitializeChecked,
+                W::AuthorizeChecked(a) => SdkIx::AuthorizeChecked(auth(a)),
+                W::AuthorizeCheckedWithSeed(a) => SdkIx::AuthorizeCheckedWithSeed(sdk::AuthorizeCheckedWithSeedArgs {
+                    stake_authorize: auth(a.stake_authorize),
+                    authority_seed: $seed(a.authority_seed),
+                    authority_owner: pk(a.authority_owner),
+                }),
+                W::SetLockupChecked(a) => SdkIx::SetLockupChecked(LockupCheckedArgs { unix_timestamp: a.unix_timestamp, epoch: a.epoch }),
+                W::GetMinimumDelegation => SdkIx::GetMinimumDelegation,
+                W::DeactivateDelinquent => SdkIx::DeactivateDelinquent,
+                W::Redelegate => SdkIx::Redelegate,
+                W::MoveStake(l) => SdkIx::MoveStake(l),
+                W::MoveLamports(l) => SdkIx::MoveLamports(l),
+            }
+        }};
+    }
+
+    fn decode_bincode(data: &[u8]) -> SdkIx {
+        let ix = bincode::deserialize::<super::StakeInstruction>(data).expect("wire decode");
+        wire_to_sdk!(wire, ix, |s: String| s)
+    }
+
+    fn decode_manual(data: &[u8]) -> SdkIx {
+        let ix = crate::wire_sbf::deserialize(data).expect("sbf decode");
+        wire_to_sdk!(wire_sbf, ix, |s: &[u8]| String::from_utf8(s.to_vec()).expect("utf8 seed"))
+    }
+
+    #[test]
+    fn every_variant_roundtrips_against_sdk() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..ROUNDS {
+            for ix in sdk_cases(&mut rng) {
+                let expected: SdkIx = bincode::deserialize(&ix.data).expect("sdk decode");
+                assert_eq!(decode_bincode(&ix.data), expected, "bincode drift for {:?}", expected);
+                assert_eq!(decode_manual(&ix.data), expected, "manual reader drift for {:?}", expected);
+            }
+        }
+    }
+
+    #[test]
+    fn move_variants_roundtrip_extreme_lamports() {
+        let (a, b, c) = (SdkPubkey::new_unique(), SdkPubkey::new_unique(), SdkPubkey::new_unique());
+        for lamports in [0u64, 1, u32::MAX as u64, u64::MAX - 1, u64::MAX] {
+            let mv = sdk::move_stake(&a, &b, &c, lamports);
+            let ml = sdk::move_lamports(&a, &b, &c, lamports);
+            for decode in [decode_bincode, decode_manual] {
+                assert_eq!(decode(&mv.data), SdkIx::MoveStake(lamports));
+                assert_eq!(decode(&ml.data), SdkIx::MoveLamports(lamports));
+            }
+        }
+    }
+
+    #[test]
+    fn authorized_keeps_roles_in_place() {
+        let a: StateAuthorized = super::Authorized { staker: [1u8; 32], withdrawer: [2u8; 32] }.into();
+        assert_eq!(a.staker, [1u8; 32]);
+        assert_eq!(a.withdrawer, [2u8; 32]);
+    }
+
+    #[test]
+    fn lockup_copies_every_field() {
+        let l: StateLockup = super::Lockup { unix_timestamp: -7, epoch: u64::MAX, custodian: [3u8; 32] }.into();
+        assert_eq!(l, StateLockup { unix_timestamp: -7, epoch: u64::MAX, custodian: [3u8; 32] });
+    }
+
+    #[test]
+    fn stake_authorize_converts_both_ways() {
+        use crate::state::StakeAuthorize;
+        use super::StakeAuthorize as W;
+        for (w, s) in [(W::Staker, StakeAuthorize::Staker), (W::Withdrawer, StakeAuthorize::Withdrawer)] {
+            assert_eq!(StakeAuthorize::from(w), s);
+            assert_eq!(W::from(s), w);
+        }
+    }
+}
@@ -87,3 +87,62 @@ async fn authorize_checked_staker_success() {
         other => panic!("expected Initialized/Stake, got {:?}", other),
     }
 }
+
+#[tokio::test]
+async fn authorize_checked_requires_new_and_current_authority_signatures() {
+    use solana_sdk::{
+        instruction::InstructionError,
+        stake::state::StakeAuthorize,
+        transaction::TransactionError,
+    };
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_account = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let create_ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake_account.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &program_id,
+    );
+    let init_ix = ixn::initialize_checked(
+        &stake_account.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create_ix, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_account, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    let before = ctx.banks_client.get_account(stake_account.pubkey()).await.unwrap().unwrap();
+
+    let new_authority = Keypair::new();
+    for (role, current) in [(StakeAuthorize::Staker, &staker), (StakeAuthorize::Withdrawer, &withdrawer)] {
+        // Drop the signer flag from one of the two authorities; only the other one signs
+        for (unsigned, signer) in [(&new_authority, current), (current, &new_authority)] {
+            let mut ix = ixn::authorize_checked(&stake_account.pubkey(), &current.pubkey(), &new_authority.pubkey(), role, None);
+            for meta in ix.accounts.iter_mut().filter(|m| m.pubkey == unsigned.pubkey()) {
+                meta.is_signer = false;
+            }
+            let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+            let mut tx = Transaction::new_unsigned(msg);
+            tx.try_sign(&[&ctx.payer, signer], ctx.last_blockhash).unwrap();
+            let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+            assert_eq!(
+                err,
+                TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+                "{:?} with {} unsigned",
+                role,
+                if unsigned.pubkey() == new_authority.pubkey() { "new authority" } else { "current authority" }
+            );
+        }
+    }
+
+    let after = ctx.banks_client.get_account(stake_account.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.data, before.data);
+}
@@ -186,3 +186,75 @@ async fn authorize_nonchecked_missing_old_signer_fails() {
         other => panic!("unexpected banks client error: {:?}", other),
     }
 }
+
+// Initialized stake with distinct staker and withdrawer
+async fn create_initialized(ctx: &mut ProgramTestContext, staker: &Keypair, withdrawer: &Keypair) -> Keypair {
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), rent.minimum_balance(space as usize), space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    stake
+}
+
+#[tokio::test]
+async fn authorize_staker_change_signer_scope() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = create_initialized(&mut ctx, &staker, &withdrawer).await;
+
+    // Unrelated signer: rejected
+    let stranger = Keypair::new();
+    let ix = ixn::authorize(&stake.pubkey(), &stranger.pubkey(), &stranger.pubkey(), StakeAuthorize::Staker, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stranger], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    // Current staker alone: no withdrawer signature needed
+    let staker_b = Keypair::new();
+    let ix = ixn::authorize(&stake.pubkey(), &staker.pubkey(), &staker_b.pubkey(), StakeAuthorize::Staker, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Withdrawer alone may also reassign the staker (native rule)
+    let staker_c = Keypair::new();
+    let ix = ixn::authorize(&stake.pubkey(), &withdrawer.pubkey(), &staker_c.pubkey(), StakeAuthorize::Staker, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // The replaced staker no longer qualifies
+    let ix = ixn::authorize(&stake.pubkey(), &staker.pubkey(), &staker.pubkey(), StakeAuthorize::Staker, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match SS::deserialize(&acct.data).unwrap() {
+        SS::Initialized(meta) => {
+            assert_eq!(meta.authorized.staker, staker_c.pubkey().to_bytes());
+            assert_eq!(meta.authorized.withdrawer, withdrawer.pubkey().to_bytes());
+        }
+        other => panic!("unexpected state: {:?}", other),
+    }
+}
@@ -221,3 +221,78 @@ async fn authorize_with_seed_withdrawer_lockup_requires_custodian() {
     let res = ctx.banks_client.process_transaction(tx).await;
     assert!(res.is_ok(), "AuthorizeWithSeed withdrawer with custodian should succeed: {:?}", res);
 }
+
+// Native ABI for AuthorizeCheckedWithSeed is [stake, base, clock, new_authority, (custodian?)].
+// The same keys with base and new authority swapped must not be accepted.
+#[tokio::test]
+async fn authorize_checked_with_seed_canonical_order_only() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let base = Keypair::new();
+    let seed = "seed-acs-order";
+    let owner = solana_sdk::system_program::id();
+    let derived_staker = Pubkey::create_with_seed(&base.pubkey(), seed, &owner).unwrap();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as usize;
+    let reserve = rent.minimum_balance(space);
+    let stake = create_stake_account(&mut ctx, reserve, &program_id).await;
+
+    let withdrawer = Keypair::new();
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: derived_staker, withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let new_staker = Keypair::new();
+    let canonical = ixn::authorize_checked_with_seed(
+        &stake.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &new_staker.pubkey(),
+        StakeAuthorize::Staker,
+        None,
+    );
+    let keys: Vec<Pubkey> = canonical.accounts.iter().map(|m| m.pubkey).collect();
+    assert_eq!(
+        &keys[..4],
+        &[stake.pubkey(), base.pubkey(), solana_sdk::sysvar::clock::id(), new_staker.pubkey()]
+    );
+
+    // Conflicting order: [stake, new_authority, clock, base]
+    let mut swapped = canonical.clone();
+    swapped.accounts.swap(1, 3);
+    let msg = Message::new(&[swapped], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base, &new_staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::MissingRequiredSignature
+        )
+    );
+
+    // Canonical order succeeds and rotates the staker
+    refresh_blockhash(&mut ctx).await;
+    let msg = Message::new(&[canonical], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base, &new_staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Initialized(meta) => {
+            assert_eq!(meta.authorized.staker, new_staker.pubkey().to_bytes());
+        }
+        other => panic!("unexpected state: {:?}", other),
+    }
+}
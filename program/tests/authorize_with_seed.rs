@@ -313,3 +313,267 @@ async fn authorize_with_seed_wrong_owner_or_seed_fails() {
     let res = ctx.banks_client.process_transaction(tx).await;
     assert!(res.is_err(), "authorize_with_seed with wrong owner should fail");
 }
+
+// Seed-derived withdrawer under an in-force lockup: the custodian must be a trailing signer
+#[tokio::test]
+async fn authorize_with_seed_withdrawer_requires_custodian_when_locked() {
+    use solana_sdk::stake::state::Lockup;
+    use solana_sdk::transaction::TransactionError;
+    use solana_sdk::instruction::InstructionError;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let staker = Keypair::new();
+    let custodian = Keypair::new();
+    let base = Keypair::new();
+    let seed = "seed-for-withdrawer";
+    let owner = solana_sdk::system_program::id();
+    let derived_withdrawer = Pubkey::create_with_seed(&base.pubkey(), seed, &owner).unwrap();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let init_ix = ixn::initialize(
+        &stake_acc.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: derived_withdrawer },
+        &Lockup { unix_timestamp: 0, epoch: u64::MAX, custodian: custodian.pubkey() },
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let new_withdrawer = Keypair::new();
+
+    // Without the custodian: rejected
+    let ix = ixn::authorize_with_seed(
+        &stake_acc.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &new_withdrawer.pubkey(),
+        StakeAuthorize::Withdrawer,
+        None,
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    // With the custodian trailing as a signer: accepted
+    let ix = ixn::authorize_with_seed(
+        &stake_acc.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &new_withdrawer.pubkey(),
+        StakeAuthorize::Withdrawer,
+        Some(&custodian.pubkey()),
+    );
+    assert!(ix.accounts.last().map_or(false, |m| m.pubkey == custodian.pubkey() && m.is_signer));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base, &custodian], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Initialized(meta) => {
+            assert_eq!(meta.authorized.withdrawer, new_withdrawer.pubkey().to_bytes());
+            assert_eq!(meta.authorized.staker, staker.pubkey().to_bytes());
+        }
+        other => panic!("unexpected state: {:?}", other),
+    }
+}
+
+// Uninitialized stake: rejected as InvalidAccountData before base lookup or derivation,
+// so even a non-signing base (which would otherwise fail the signer check) sees it
+#[tokio::test]
+async fn authorize_with_seed_uninitialized_stake_fails_early() {
+    use solana_sdk::transaction::TransactionError;
+    use solana_sdk::instruction::InstructionError;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let base = Keypair::new();
+    let seed = "seed-uninit";
+    let owner = solana_sdk::system_program::id();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake_acc.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &program_id,
+    );
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Base does not sign; an unrelated trailing signer satisfies the entrypoint's any-signer gate
+    let other = Keypair::new();
+    let mut ix = ixn::authorize_with_seed_no_base_signer(
+        &stake_acc.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &Keypair::new().pubkey(),
+        StakeAuthorize::Staker,
+        None,
+    );
+    ix.accounts.push(AccountMeta::new_readonly(other.pubkey(), true));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &other], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+
+    let acct = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap(),
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Uninitialized
+    ));
+}
+
+// The custodian is only taken from the metas after base: a signing custodian placed
+// ahead of base does not lift an in-force lockup
+#[tokio::test]
+async fn authorize_with_seed_ignores_custodian_ahead_of_base() {
+    use solana_sdk::stake::state::Lockup;
+    use solana_sdk::transaction::TransactionError;
+    use solana_sdk::instruction::InstructionError;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let custodian = Keypair::new();
+    let base = Keypair::new();
+    let seed = "seed-custodian-order";
+    let owner = solana_sdk::system_program::id();
+    let derived_withdrawer = Pubkey::create_with_seed(&base.pubkey(), seed, &owner).unwrap();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let init_ix = ixn::initialize(
+        &stake_acc.pubkey(),
+        &Authorized { staker: Pubkey::new_unique(), withdrawer: derived_withdrawer },
+        &Lockup { unix_timestamp: 0, epoch: u64::MAX, custodian: custodian.pubkey() },
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // [stake, custodian, clock, base]
+    let mut ix = ixn::authorize_with_seed(
+        &stake_acc.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &Keypair::new().pubkey(),
+        StakeAuthorize::Withdrawer,
+        Some(&custodian.pubkey()),
+    );
+    let custodian_meta = ix.accounts.pop().unwrap();
+    assert_eq!(custodian_meta.pubkey, custodian.pubkey());
+    ix.accounts.insert(1, custodian_meta);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base, &custodian], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    let acct = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Initialized(meta) => {
+            assert_eq!(meta.authorized.withdrawer, derived_withdrawer.to_bytes());
+        }
+        other => panic!("unexpected state: {:?}", other),
+    }
+}
+
+// AuthorizeCheckedWithSeed on the withdrawer under an in-force lockup: the custodian
+// must sign in the trailing slot after the new authority
+#[tokio::test]
+async fn authorize_checked_with_seed_withdrawer_requires_custodian_when_locked() {
+    use solana_sdk::stake::state::Lockup;
+    use solana_sdk::transaction::TransactionError;
+    use solana_sdk::instruction::InstructionError;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let custodian = Keypair::new();
+    let base = Keypair::new();
+    let seed = "seed-acws-custodian";
+    let owner = solana_sdk::system_program::id();
+    let derived_withdrawer = Pubkey::create_with_seed(&base.pubkey(), seed, &owner).unwrap();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let init_ix = ixn::initialize(
+        &stake_acc.pubkey(),
+        &Authorized { staker: Pubkey::new_unique(), withdrawer: derived_withdrawer },
+        &Lockup { unix_timestamp: 0, epoch: u64::MAX, custodian: custodian.pubkey() },
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let new_withdrawer = Keypair::new();
+
+    // Without the custodian: rejected
+    let ix = ixn::authorize_checked_with_seed(
+        &stake_acc.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &new_withdrawer.pubkey(),
+        StakeAuthorize::Withdrawer,
+        None,
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base, &new_withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    // With the custodian trailing as a signer: accepted
+    let ix = ixn::authorize_checked_with_seed(
+        &stake_acc.pubkey(),
+        &base.pubkey(),
+        seed.to_string(),
+        &owner,
+        &new_withdrawer.pubkey(),
+        StakeAuthorize::Withdrawer,
+        Some(&custodian.pubkey()),
+    );
+    assert!(ix.accounts.last().map_or(false, |m| m.pubkey == custodian.pubkey() && m.is_signer));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &base, &new_withdrawer, &custodian], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Initialized(meta) => {
+            assert_eq!(meta.authorized.withdrawer, new_withdrawer.pubkey().to_bytes());
+        }
+        other => panic!("unexpected state: {:?}", other),
+    }
+}
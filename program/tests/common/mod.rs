@@ -52,6 +52,18 @@ pub async fn refresh_blockhash(ctx: &mut ProgramTestContext) {
         .unwrap();
 }
 
+// Advance exactly `n` epochs, landing on the first slot of the target epoch
+// (the epoch schedule accounts for warmup and `first_normal_slot`)
+pub async fn warp_epochs(ctx: &mut ProgramTestContext, n: u64) {
+    if n == 0 {
+        return;
+    }
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let target = ctx.genesis_config().epoch_schedule.get_first_slot_in_epoch(clock.epoch + n);
+    ctx.warp_to_slot(target).unwrap();
+    refresh_blockhash(ctx).await;
+}
+
 pub async fn transfer(ctx: &mut ProgramTestContext, recipient: &Pubkey, amount: u64) {
     let tx = Transaction::new_signed_with_payer(
         &[system_instruction::transfer(&ctx.payer.pubkey(), recipient, amount)],
@@ -243,6 +243,27 @@ pub mod ixn {
         sdk_ixn::move_lamports(source, dest, staker, lamports)
     }
 
+    // Redelegate: [stake, uninitialized_stake, vote, stake_config, staker]
+    // The SDK builder also allocates/assigns the target; tests pre-create it instead.
+    #[allow(deprecated)]
+    pub fn redelegate(stake: &Pubkey, staker: &Pubkey, vote: &Pubkey, uninitialized_stake: &Pubkey) -> Instruction {
+        #[allow(deprecated)]
+        let data = bincode::serialize(&sdk_ixn::StakeInstruction::Redelegate).unwrap();
+        #[allow(deprecated)]
+        let config = solana_sdk::stake::config::id();
+        Instruction {
+            program_id: stake_program_id(),
+            accounts: vec![
+                AccountMeta::new(*stake, false),
+                AccountMeta::new(*uninitialized_stake, false),
+                AccountMeta::new_readonly(*vote, false),
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new_readonly(*staker, true),
+            ],
+            data,
+        }
+    }
+
     // DeactivateDelinquent: [stake, delinquent_vote, reference_vote]
     pub fn deactivate_delinquent(stake: &Pubkey, delinquent_vote: &Pubkey, reference_vote: &Pubkey) -> Instruction {
         // For test robustness, target our stake program directly and use empty data
@@ -264,6 +285,70 @@ pub mod ixn {
 pub use ixn::*;
 
 // ---------- State helpers ----------
+// Single place for pinocchio <-> SDK state conversion; pinocchio keeps
+// numeric fields as LE byte arrays and pubkeys as raw [u8; 32].
+pub fn meta_to_sdk(meta: &pinocchio_stake::state::Meta) -> Meta {
+    Meta {
+        authorized: Authorized {
+            staker: Pubkey::new_from_array(meta.authorized.staker),
+            withdrawer: Pubkey::new_from_array(meta.authorized.withdrawer),
+        },
+        rent_exempt_reserve: u64::from_le_bytes(meta.rent_exempt_reserve),
+        lockup: Lockup {
+            unix_timestamp: meta.lockup.unix_timestamp,
+            epoch: meta.lockup.epoch,
+            custodian: Pubkey::new_from_array(meta.lockup.custodian),
+        },
+    }
+}
+
+pub fn meta_from_sdk(meta: &Meta) -> pinocchio_stake::state::Meta {
+    pinocchio_stake::state::Meta {
+        rent_exempt_reserve: meta.rent_exempt_reserve.to_le_bytes(),
+        authorized: pinocchio_stake::state::accounts::Authorized {
+            staker: meta.authorized.staker.to_bytes(),
+            withdrawer: meta.authorized.withdrawer.to_bytes(),
+        },
+        lockup: pinocchio_stake::state::Lockup {
+            unix_timestamp: meta.lockup.unix_timestamp,
+            epoch: meta.lockup.epoch,
+            custodian: meta.lockup.custodian.to_bytes(),
+        },
+    }
+}
+
+pub fn stake_to_sdk(stake: &pinocchio_stake::state::delegation::Stake) -> Stake {
+    let del = &stake.delegation;
+    #[allow(deprecated)]
+    let warmup_cooldown_rate = f64::from_bits(u64::from_le_bytes(del.warmup_cooldown_rate));
+    Stake {
+        delegation: solana_sdk::stake::state::Delegation {
+            voter_pubkey: Pubkey::new_from_array(del.voter_pubkey),
+            stake: u64::from_le_bytes(del.stake),
+            activation_epoch: u64::from_le_bytes(del.activation_epoch),
+            deactivation_epoch: u64::from_le_bytes(del.deactivation_epoch),
+            warmup_cooldown_rate,
+        },
+        credits_observed: u64::from_le_bytes(stake.credits_observed),
+    }
+}
+
+pub fn stake_from_sdk(stake: &Stake) -> pinocchio_stake::state::delegation::Stake {
+    let del = &stake.delegation;
+    #[allow(deprecated)]
+    let delegation = pinocchio_stake::state::delegation::Delegation {
+        voter_pubkey: del.voter_pubkey.to_bytes(),
+        stake: del.stake.to_le_bytes(),
+        activation_epoch: del.activation_epoch.to_le_bytes(),
+        deactivation_epoch: del.deactivation_epoch.to_le_bytes(),
+        warmup_cooldown_rate: del.warmup_cooldown_rate.to_bits().to_le_bytes(),
+    };
+    pinocchio_stake::state::delegation::Stake {
+        delegation,
+        credits_observed: stake.credits_observed.to_le_bytes(),
+    }
+}
+
 pub async fn get_stake_account(
     banks_client: &mut BanksClient,
     pubkey: &Pubkey,
@@ -273,47 +358,9 @@ pub async fn get_stake_account(
     let lamports = stake_account.lamports;
     let st = pstate::stake_state_v2::StakeStateV2::deserialize(&stake_account.data).unwrap();
     match st {
-        pstate::stake_state_v2::StakeStateV2::Initialized(meta) => {
-            let meta_sdk = Meta {
-                authorized: Authorized {
-                    staker: Pubkey::new_from_array(meta.authorized.staker),
-                    withdrawer: Pubkey::new_from_array(meta.authorized.withdrawer),
-                },
-                rent_exempt_reserve: u64::from_le_bytes(meta.rent_exempt_reserve),
-                lockup: Lockup {
-                    unix_timestamp: meta.lockup.unix_timestamp,
-                    epoch: meta.lockup.epoch,
-                    custodian: Pubkey::new_from_array(meta.lockup.custodian),
-                },
-            };
-            (meta_sdk, None, lamports)
-        }
+        pstate::stake_state_v2::StakeStateV2::Initialized(meta) => (meta_to_sdk(&meta), None, lamports),
         pstate::stake_state_v2::StakeStateV2::Stake(meta, stake, _flags) => {
-            let meta_sdk = Meta {
-                authorized: Authorized {
-                    staker: Pubkey::new_from_array(meta.authorized.staker),
-                    withdrawer: Pubkey::new_from_array(meta.authorized.withdrawer),
-                },
-                rent_exempt_reserve: u64::from_le_bytes(meta.rent_exempt_reserve),
-                lockup: Lockup {
-                    unix_timestamp: meta.lockup.unix_timestamp,
-                    epoch: meta.lockup.epoch,
-                    custodian: Pubkey::new_from_array(meta.lockup.custodian),
-                },
-            };
-            let del = &stake.delegation;
-            let delegation_sdk = solana_sdk::stake::state::Delegation {
-                voter_pubkey: Pubkey::new_from_array(del.voter_pubkey),
-                stake: u64::from_le_bytes(del.stake),
-                activation_epoch: u64::from_le_bytes(del.activation_epoch),
-                deactivation_epoch: u64::from_le_bytes(del.deactivation_epoch),
-                warmup_cooldown_rate: f64::from_bits(u64::from_le_bytes(del.warmup_cooldown_rate)),
-            };
-            let stake_sdk = Stake {
-                delegation: delegation_sdk,
-                credits_observed: u64::from_le_bytes(stake.credits_observed),
-            };
-            (meta_sdk, Some(stake_sdk), lamports)
+            (meta_to_sdk(&meta), Some(stake_to_sdk(&stake)), lamports)
         }
         pstate::stake_state_v2::StakeStateV2::Uninitialized => panic!("panic: uninitialized"),
         _ => unimplemented!(),
@@ -332,6 +379,81 @@ pub fn encode_program_stake_state(st: &pinocchio_stake::state::stake_state_v2::S
     buf
 }
 
+pub fn state_from_sdk(st: &solana_sdk::stake::state::StakeStateV2) -> pinocchio_stake::state::stake_state_v2::StakeStateV2 {
+    use pinocchio_stake::state::{stake_flag::StakeFlags, stake_state_v2::StakeStateV2 as PinState};
+    use solana_sdk::stake::{stake_flags::StakeFlags as SdkFlags, state::StakeStateV2 as SdkState};
+    match st {
+        SdkState::Uninitialized => PinState::Uninitialized,
+        SdkState::Initialized(meta) => PinState::Initialized(meta_from_sdk(meta)),
+        SdkState::Stake(meta, stake, flags) => {
+            let flags = if flags.contains(SdkFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED) {
+                StakeFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED
+            } else {
+                StakeFlags::empty()
+            };
+            PinState::Stake(meta_from_sdk(meta), stake_from_sdk(stake), flags)
+        }
+        SdkState::RewardsPool => PinState::RewardsPool,
+    }
+}
+
+/// `assert_eq!` for stake states that names each differing field instead of
+/// printing both states in full.
+#[track_caller]
+pub fn assert_states_eq(
+    left: &pinocchio_stake::state::stake_state_v2::StakeStateV2,
+    right: &pinocchio_stake::state::stake_state_v2::StakeStateV2,
+) {
+    let diffs = state_diffs(left, right);
+    assert!(diffs.is_empty(), "stake states differ (left != right):\n  {}", diffs.join("\n  "));
+}
+
+fn state_diffs(
+    left: &pinocchio_stake::state::stake_state_v2::StakeStateV2,
+    right: &pinocchio_stake::state::stake_state_v2::StakeStateV2,
+) -> Vec<String> {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as PinState;
+    fn push<T: PartialEq + std::fmt::Debug>(out: &mut Vec<String>, field: &str, l: T, r: T) {
+        if l != r {
+            out.push(format!("{}: {:?} != {:?}", field, l, r));
+        }
+    }
+    fn meta_diffs(out: &mut Vec<String>, l: &pinocchio_stake::state::Meta, r: &pinocchio_stake::state::Meta) {
+        let (l, r) = (meta_to_sdk(l), meta_to_sdk(r));
+        push(out, "meta.rent_exempt_reserve", l.rent_exempt_reserve, r.rent_exempt_reserve);
+        push(out, "meta.authorized.staker", l.authorized.staker, r.authorized.staker);
+        push(out, "meta.authorized.withdrawer", l.authorized.withdrawer, r.authorized.withdrawer);
+        push(out, "meta.lockup.unix_timestamp", l.lockup.unix_timestamp, r.lockup.unix_timestamp);
+        push(out, "meta.lockup.epoch", l.lockup.epoch, r.lockup.epoch);
+        push(out, "meta.lockup.custodian", l.lockup.custodian, r.lockup.custodian);
+    }
+    fn variant(st: &PinState) -> &'static str {
+        match st {
+            PinState::Uninitialized => "Uninitialized",
+            PinState::Initialized(_) => "Initialized",
+            PinState::Stake(..) => "Stake",
+            PinState::RewardsPool => "RewardsPool",
+        }
+    }
+
+    let mut out = Vec::new();
+    match (left, right) {
+        (PinState::Initialized(lm), PinState::Initialized(rm)) => meta_diffs(&mut out, lm, rm),
+        (PinState::Stake(lm, ls, lf), PinState::Stake(rm, rs, rf)) => {
+            meta_diffs(&mut out, lm, rm);
+            let (ls, rs) = (stake_to_sdk(ls), stake_to_sdk(rs));
+            push(&mut out, "delegation.voter_pubkey", ls.delegation.voter_pubkey, rs.delegation.voter_pubkey);
+            push(&mut out, "delegation.stake", ls.delegation.stake, rs.delegation.stake);
+            push(&mut out, "delegation.activation_epoch", ls.delegation.activation_epoch, rs.delegation.activation_epoch);
+            push(&mut out, "delegation.deactivation_epoch", ls.delegation.deactivation_epoch, rs.delegation.deactivation_epoch);
+            push(&mut out, "credits_observed", ls.credits_observed, rs.credits_observed);
+            push(&mut out, "flags", lf, rf);
+        }
+        (l, r) => push(&mut out, "variant", variant(l), variant(r)),
+    }
+    out
+}
+
 // ---------- Error helpers ----------
 pub mod err {
     use solana_sdk::{program_error::ProgramError, stake::instruction::StakeError};
@@ -343,6 +465,8 @@ pub mod err {
             (ProgramError::Custom(0x13), StakeError::VoteAddressMismatch) => true,
             (ProgramError::Custom(0x14), StakeError::MergeMismatch) => true,
             (ProgramError::Custom(0x15), StakeError::LockupInForce) => true,
+            (ProgramError::Custom(0x16), StakeError::InsufficientReferenceVotes) => true,
+            (ProgramError::Custom(0x17), StakeError::MinimumDelinquentEpochsForDeactivationNotMet) => true,
             (ProgramError::Custom(0x18), StakeError::TooSoonToRedelegate) => true,
             _ => *e == expected.into(),
         }
@@ -186,3 +186,160 @@ async fn deactivate_missing_staker_signature_fails() {
         other => panic!("unexpected banks client error: {:?}", other),
     }
 }
+
+#[tokio::test]
+async fn deactivate_with_explicit_clock_meta_succeeds() {
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let create_stake = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        reserve,
+        space,
+        &program_id,
+    );
+    let msg = Message::new(&[create_stake], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let vote_acc = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote_acc).await;
+    let min_del = common::get_minimum_delegation_lamports(&mut ctx).await;
+    transfer(&mut ctx, &stake.pubkey(), min_del).await;
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote_acc.pubkey());
+    let msg = Message::new(&[del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Deactivate with the native meta shape [stake, clock, staker]; the handler reads the
+    // clock through the sysvar, so the clock meta is accepted without a separate key check.
+    let deact_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(stake.pubkey(), false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+            AccountMeta::new_readonly(staker.pubkey(), true),
+        ],
+        data: vec![5u8],
+    };
+    let msg = Message::new(&[deact_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_meta, stake_data, _flags) => {
+            assert_eq!(u64::from_le_bytes(stake_data.delegation.deactivation_epoch), clock.epoch);
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+}
+
+// The program's own id passed as the stake account fails cleanly for mutating instructions
+#[tokio::test]
+async fn program_id_as_stake_account_is_rejected() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let authority = Keypair::new();
+
+    let args = solana_sdk::stake::instruction::LockupArgs { unix_timestamp: Some(1), epoch: None, custodian: None };
+    for ix in [
+        ixn::deactivate_stake(&program_id, &authority.pubkey()),
+        ixn::set_lockup_checked(&program_id, &args, &authority.pubkey()),
+    ] {
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &authority], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        match err {
+            TransactionError::InstructionError(0, ie) => assert!(
+                matches!(
+                    ie,
+                    InstructionError::InvalidAccountOwner
+                        | InstructionError::InvalidArgument
+                        | InstructionError::InvalidInstructionData
+                ),
+                "unexpected error: {:?}",
+                ie
+            ),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn deactivate_requires_staker_not_withdrawer() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min_del = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let vote_acc = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote_acc).await;
+
+    let create_stake = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min_del, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote_acc.pubkey());
+    let msg = Message::new(&[create_stake, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // The withdrawer holds more authority but is not the deactivation role
+    let ix = ixn::deactivate_stake(&stake.pubkey(), &withdrawer.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    let ix = ixn::deactivate_stake(&stake.pubkey(), &staker.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_, s, _) => {
+            assert_eq!(u64::from_le_bytes(s.delegation.deactivation_epoch), clock.epoch);
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+}
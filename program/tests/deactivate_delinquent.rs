@@ -61,10 +61,7 @@ async fn deactivate_delinquent_happy_path() {
     let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
 
     // Warp to epoch 5 so that reference sequence [1..5] matches and min_epoch = 0
-    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
-    let first_normal = ctx.genesis_config().epoch_schedule.first_normal_slot;
-    let target_slot = first_normal + slots_per_epoch * 5 + 1;
-    ctx.warp_to_slot(target_slot).unwrap();
+    common::warp_epochs(&mut ctx, 5).await;
 
     // Rewrite vote accounts' data to align with the actual current epoch
     let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
@@ -306,6 +303,223 @@ async fn deactivate_delinquent_not_delinquent_enough_fails() {
     let res = ctx.banks_client.process_transaction(tx).await;
     assert!(res.is_err(), "expected failure due to insufficient delinquency");
 }
+// Cluster younger than the delinquency window => clean MinimumDelinquentEpochs error
+#[tokio::test]
+async fn deactivate_delinquent_before_window_elapsed_fails_cleanly() {
+    let mut pt = common::program_test();
+    let reference_vote = Pubkey::new_unique();
+    let delinquent_vote = Pubkey::new_unique();
+    for key in [reference_vote, delinquent_vote] {
+        pt.add_account(
+            key,
+            SolanaAccount { lamports: 1_000_000, data: vec![], owner: solana_sdk::vote::program::id(), executable: false, rent_epoch: 0 }
+        );
+    }
+
+    let mut ctx = pt.start_with_context().await;
+    let n = pinocchio_stake::helpers::constant::MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION;
+    // Warp to epoch n-1: the reference can cover 0..=n-1, but current - n underflows
+    common::warp_epochs(&mut ctx, n - 1).await;
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    assert!(clock.epoch < n, "test requires a cluster younger than the window");
+
+    let start = clock.epoch.saturating_sub(n - 1);
+    let seq: Vec<_> = (start..=clock.epoch).map(|e| (e, 1, 0)).collect();
+    let mut acc = ctx.banks_client.get_account(reference_vote).await.unwrap().unwrap();
+    acc.data = build_epoch_credits_bytes(&seq);
+    ctx.set_account(&reference_vote, &acc.into());
+    // Oldest possible vote; still not delinquent this early
+    let mut acc2 = ctx.banks_client.get_account(delinquent_vote).await.unwrap().unwrap();
+    acc2.data = build_epoch_credits_bytes(&[(0, 1, 0)]);
+    ctx.set_account(&delinquent_vote, &acc2.into());
+
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let stake = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(), &stake.pubkey(), reserve, space, &program_id,
+    );
+    let init_ix = Instruction { program_id, accounts: vec![
+        AccountMeta::new(stake.pubkey(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(staker.pubkey(), false),
+        AccountMeta::new_readonly(withdrawer.pubkey(), true),
+    ], data: vec![9u8] };
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dd_ix = ixn::deactivate_delinquent(&stake.pubkey(), &delinquent_vote, &reference_vote);
+    let msg = Message::new(&[dd_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    match err {
+        solana_sdk::transaction::TransactionError::InstructionError(0, ie) => {
+            let pe = solana_sdk::program_error::ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(
+                &pe,
+                solana_sdk::stake::instruction::StakeError::MinimumDelinquentEpochsForDeactivationNotMet
+            ));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+// Reference has not voted yet this epoch: its window ends at current-1 and is still accepted
+#[tokio::test]
+async fn deactivate_delinquent_reference_window_ending_previous_epoch() {
+    let mut pt = common::program_test();
+    let reference_vote = Pubkey::new_unique();
+    let delinquent_vote = Pubkey::new_unique();
+    for key in [reference_vote, delinquent_vote] {
+        pt.add_account(
+            key,
+            SolanaAccount { lamports: 1_000_000, data: vec![], owner: solana_sdk::vote::program::id(), executable: false, rent_epoch: 0 }
+        );
+    }
+
+    let mut ctx = pt.start_with_context().await;
+    let n = pinocchio_stake::helpers::constant::MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION;
+    // One epoch past the window so that current-1 still has n epochs behind it
+    common::warp_epochs(&mut ctx, n + 1).await;
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    assert!(clock.epoch > n);
+
+    // Newest credited epoch is exactly current-1
+    let newest = clock.epoch - 1;
+    let seq: Vec<_> = (newest + 1 - n..=newest).map(|e| (e, 1, 0)).collect();
+    let mut acc = ctx.banks_client.get_account(reference_vote).await.unwrap().unwrap();
+    acc.data = build_epoch_credits_bytes(&seq);
+    ctx.set_account(&reference_vote, &acc.into());
+    let mut acc2 = ctx.banks_client.get_account(delinquent_vote).await.unwrap().unwrap();
+    acc2.data = build_epoch_credits_bytes(&[(clock.epoch - n, 1, 0)]);
+    ctx.set_account(&delinquent_vote, &acc2.into());
+
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let stake = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(), &stake.pubkey(), reserve + min, space, &program_id,
+    );
+    let init_ix = Instruction { program_id, accounts: vec![
+        AccountMeta::new(stake.pubkey(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(staker.pubkey(), false),
+        AccountMeta::new_readonly(withdrawer.pubkey(), true),
+    ], data: vec![9u8] };
+    let del_ix = Instruction { program_id, accounts: vec![
+        AccountMeta::new(stake.pubkey(), false),
+        AccountMeta::new_readonly(delinquent_vote, false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+        AccountMeta::new_readonly(staker.pubkey(), true),
+    ], data: vec![2u8] };
+    let msg = Message::new(&[create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dd_ix = ixn::deactivate_delinquent(&stake.pubkey(), &delinquent_vote, &reference_vote);
+    let msg = Message::new(&[dd_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+    let res = ctx.banks_client.process_transaction(tx).await;
+    assert!(res.is_ok(), "reference window ending at current-1 should be accepted: {:?}", res);
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_meta, stake_data, _flags) => {
+            assert_eq!(u64::from_le_bytes(stake_data.delegation.deactivation_epoch), clock.epoch);
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+}
+
+// A vote account with no epoch credits never voted: maximally delinquent
+#[tokio::test]
+async fn deactivate_delinquent_never_voted_vote_account() {
+    let mut pt = common::program_test();
+    let reference_vote = Pubkey::new_unique();
+    let delinquent_vote = Pubkey::new_unique();
+    for key in [reference_vote, delinquent_vote] {
+        pt.add_account(
+            key,
+            SolanaAccount { lamports: 1_000_000, data: vec![], owner: solana_sdk::vote::program::id(), executable: false, rent_epoch: 0 }
+        );
+    }
+
+    let mut ctx = pt.start_with_context().await;
+    let n = pinocchio_stake::helpers::constant::MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION;
+    common::warp_epochs(&mut ctx, n).await;
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+
+    let seq: Vec<_> = (clock.epoch + 1 - n..=clock.epoch).map(|e| (e, 1, 0)).collect();
+    let mut acc = ctx.banks_client.get_account(reference_vote).await.unwrap().unwrap();
+    acc.data = build_epoch_credits_bytes(&seq);
+    ctx.set_account(&reference_vote, &acc.into());
+    // Zero credit entries: only the u32 length prefix
+    let mut acc2 = ctx.banks_client.get_account(delinquent_vote).await.unwrap().unwrap();
+    acc2.data = build_epoch_credits_bytes(&[]);
+    ctx.set_account(&delinquent_vote, &acc2.into());
+
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let stake = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(), &stake.pubkey(), reserve + min, space, &program_id,
+    );
+    let init_ix = Instruction { program_id, accounts: vec![
+        AccountMeta::new(stake.pubkey(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(staker.pubkey(), false),
+        AccountMeta::new_readonly(withdrawer.pubkey(), true),
+    ], data: vec![9u8] };
+    let del_ix = Instruction { program_id, accounts: vec![
+        AccountMeta::new(stake.pubkey(), false),
+        AccountMeta::new_readonly(delinquent_vote, false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+        AccountMeta::new_readonly(staker.pubkey(), true),
+    ], data: vec![2u8] };
+    let msg = Message::new(&[create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dd_ix = ixn::deactivate_delinquent(&stake.pubkey(), &delinquent_vote, &reference_vote);
+    let msg = Message::new(&[dd_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+    let res = ctx.banks_client.process_transaction(tx).await;
+    assert!(res.is_ok(), "never-voted vote account should be deactivatable: {:?}", res);
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_meta, stake_data, _flags) => {
+            assert_eq!(u64::from_le_bytes(stake_data.delegation.deactivation_epoch), clock.epoch);
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+}
+
 // Only run these when strict-authz is explicitly enabled
 #[cfg(not(feature = "strict-authz"))]
 fn main() {}
@@ -0,0 +1,77 @@
+//! Decode conformance matrix: for a fixed set of payloads sent with no accounts,
+//! pins whether the entrypoint dispatches to a handler (which then fails with
+//! `NotEnoughAccountKeys`) or rejects at decode (`InvalidInstructionData`).
+//! The column checked is the one the SBF artifact was built with:
+//! `cargo-build-sbf --no-default-features --features sbf[,compat_loose_decode|,strict-wire]`.
+//!
+//! | input                         | default              | compat_loose_decode  | strict-wire |
+//! |-------------------------------|----------------------|----------------------|-------------|
+//! | `[]`                          | DeactivateDelinquent | DeactivateDelinquent | rejected    |
+//! | `[2]`                         | DelegateStake        | DelegateStake        | rejected    |
+//! | `[5]`                         | Deactivate           | Deactivate           | rejected    |
+//! | `[14]`                        | rejected             | DeactivateDelinquent | rejected    |
+//! | `[5,0,0,0]` (full bincode)    | Deactivate           | Deactivate           | Deactivate  |
+//! | `[3,0,0,0,1,2,3]` (truncated) | rejected             | rejected             | rejected    |
+//! | `[5,0,0,0,0xAA,0xBB]` (junk)  | Deactivate           | Deactivate           | Deactivate  |
+//!
+//! Trailing bytes are accepted in every column, as native `limited_deserialize` does.
+
+mod common;
+use common::*;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    message::Message,
+    transaction::TransactionError,
+};
+
+const DISPATCHED: InstructionError = InstructionError::NotEnoughAccountKeys;
+const REJECTED: InstructionError = InstructionError::InvalidInstructionData;
+
+#[cfg(not(any(feature = "compat_loose_decode", feature = "strict-wire")))]
+const EXPECTED: [(&str, &[u8], InstructionError); 7] = [
+    ("empty", &[], DISPATCHED),
+    ("short delegate", &[2], DISPATCHED),
+    ("short deactivate", &[5], DISPATCHED),
+    ("short 14", &[14], REJECTED),
+    ("full bincode", &[5, 0, 0, 0], DISPATCHED),
+    ("truncated", &[3, 0, 0, 0, 1, 2, 3], REJECTED),
+    ("trailing junk", &[5, 0, 0, 0, 0xAA, 0xBB], DISPATCHED),
+];
+
+#[cfg(all(feature = "compat_loose_decode", not(feature = "strict-wire")))]
+const EXPECTED: [(&str, &[u8], InstructionError); 7] = [
+    ("empty", &[], DISPATCHED),
+    ("short delegate", &[2], DISPATCHED),
+    ("short deactivate", &[5], DISPATCHED),
+    ("short 14", &[14], DISPATCHED),
+    ("full bincode", &[5, 0, 0, 0], DISPATCHED),
+    ("truncated", &[3, 0, 0, 0, 1, 2, 3], REJECTED),
+    ("trailing junk", &[5, 0, 0, 0, 0xAA, 0xBB], DISPATCHED),
+];
+
+#[cfg(feature = "strict-wire")]
+const EXPECTED: [(&str, &[u8], InstructionError); 7] = [
+    ("empty", &[], REJECTED),
+    ("short delegate", &[2], REJECTED),
+    ("short deactivate", &[5], REJECTED),
+    ("short 14", &[14], REJECTED),
+    ("full bincode", &[5, 0, 0, 0], DISPATCHED),
+    ("truncated", &[3, 0, 0, 0, 1, 2, 3], REJECTED),
+    ("trailing junk", &[5, 0, 0, 0, 0xAA, 0xBB], DISPATCHED),
+];
+
+#[tokio::test]
+async fn decode_outcomes_match_conformance_table() {
+    let mut ctx = common::program_test().start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    for (name, data, expected) in EXPECTED {
+        let ix = Instruction { program_id, accounts: vec![], data: data.to_vec() };
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(err, TransactionError::InstructionError(0, expected), "input: {}", name);
+        refresh_blockhash(&mut ctx).await;
+    }
+}
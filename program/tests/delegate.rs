@@ -113,3 +113,347 @@ async fn delegate_stake_success_sets_state_and_amount() {
         other => panic!("expected Stake state, got {:?}", other),
     }
 }
+
+#[tokio::test]
+async fn delegate_same_voter_rescinds_pending_deactivation() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+
+    // Create + initialize + fund stake account
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let create_stake = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        reserve,
+        space,
+        &program_id,
+    );
+    let msg = Message::new(&[create_stake], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let extra = common::get_minimum_delegation_lamports(&mut ctx).await;
+    common::transfer(&mut ctx, &stake.pubkey(), extra).await;
+
+    let vote_a = Keypair::new();
+    let vote_b = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote_a).await;
+    create_dummy_vote_account(&mut ctx, &vote_b).await;
+
+    // Delegate to A, then deactivate in the same epoch
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote_a.pubkey());
+    let msg = Message::new(&[del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let deact_ix = ixn::deactivate_stake(&stake.pubkey(), &staker.pubkey());
+    let msg = Message::new(&[deact_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    let deactivated = match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_, s, _) => s,
+        other => panic!("expected Stake state, got {:?}", other),
+    };
+    assert_eq!(u64::from_le_bytes(deactivated.delegation.deactivation_epoch), clock.epoch);
+
+    // A different voter is still too soon while deactivation is pending
+    let del_b = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote_b.pubkey());
+    let msg = Message::new(&[del_b], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    match err {
+        solana_sdk::transaction::TransactionError::InstructionError(_, ie) => {
+            let pe = solana_sdk::program_error::ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(
+                &pe,
+                solana_sdk::stake::instruction::StakeError::TooSoonToRedelegate
+            ));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // Same voter rescinds the deactivation instead of erroring
+    refresh_blockhash(&mut ctx).await;
+    let del_a = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote_a.pubkey());
+    let msg = Message::new(&[del_a], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_, s, _) => {
+            assert_eq!(u64::from_le_bytes(s.delegation.deactivation_epoch), u64::MAX);
+            assert_eq!(s.delegation.voter_pubkey, vote_a.pubkey().to_bytes());
+            // Rescind leaves the original activation and amount untouched
+            assert_eq!(s.delegation.activation_epoch, deactivated.delegation.activation_epoch);
+            assert_eq!(s.delegation.stake, deactivated.delegation.stake);
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+}
+
+// Cluster stake history read from the bank, exposed through the program's lookup trait
+struct BankHistory(solana_sdk::stake_history::StakeHistory);
+impl pinocchio_stake::state::stake_history::StakeHistoryGetEntry for BankHistory {
+    fn get_entry(&self, epoch: u64) -> Option<pinocchio_stake::state::stake_history::StakeHistoryEntry> {
+        self.0.get(epoch).map(|e| pinocchio_stake::state::stake_history::StakeHistoryEntry {
+            effective: e.effective.to_le_bytes(),
+            activating: e.activating.to_le_bytes(),
+            deactivating: e.deactivating.to_le_bytes(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn delegation_fully_effective_after_one_epoch() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::vote::{instruction as vote_ixn, state::{VoteInit, VoteStateV3}};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    // A real vote account so the bank tracks the delegation in stake history
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let node = Keypair::new();
+    let vote = Keypair::new();
+    let mut ixs = vec![system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &node.pubkey(),
+        rent.minimum_balance(0),
+        0,
+        &solana_sdk::system_program::id(),
+    )];
+    ixs.append(&mut vote_ixn::create_account_with_config(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        &VoteInit { node_pubkey: node.pubkey(), authorized_voter: node.pubkey(), authorized_withdrawer: ctx.payer.pubkey(), commission: 0 },
+        rent.minimum_balance(VoteStateV3::size_of()),
+        vote_ixn::CreateVoteAccountConfig { space: VoteStateV3::size_of() as u64, ..Default::default() },
+    ));
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&ctx.payer.pubkey()), &[&ctx.payer, &node, &vote], ctx.last_blockhash);
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let amount = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        rent.minimum_balance(space as usize) + amount,
+        space,
+        &program_id,
+    );
+    let init_ix = ixn::initialize_checked(&stake.pubkey(), &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() });
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    common::warp_epochs(&mut ctx, 1).await;
+
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let history = BankHistory(ctx.banks_client.get_sysvar::<solana_sdk::stake_history::StakeHistory>().await.unwrap());
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    let SS::Stake(_, s, _) = SS::deserialize(&acct.data).unwrap() else {
+        panic!("expected Stake");
+    };
+    assert_eq!(u64::from_le_bytes(s.delegation.activation_epoch) + 1, clock.epoch);
+    let effective = s.delegation.stake(
+        clock.epoch.to_le_bytes(),
+        &history,
+        pinocchio_stake::helpers::PERPETUAL_NEW_WARMUP_COOLDOWN_RATE_EPOCH,
+    );
+    assert_eq!(effective, amount);
+}
+
+// Native `redelegate_stake`: while the stake is effective, only a same-voter rescind of a
+// deactivation scheduled this epoch is allowed. Re-delegating an active, non-deactivating
+// stake is TooSoonToRedelegate whether or not the voter changes, and leaves the state as is.
+#[tokio::test]
+async fn delegate_active_stake_same_or_different_voter() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let vote_a = Keypair::new();
+    let vote_b = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote_a).await;
+    create_dummy_vote_account(&mut ctx, &vote_b).await;
+
+    let create_stake = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote_a.pubkey());
+    let msg = Message::new(&[create_stake, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    common::warp_epochs(&mut ctx, 4).await;
+    let before = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+
+    for vote in [&vote_a, &vote_b] {
+        let ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        match err {
+            solana_sdk::transaction::TransactionError::InstructionError(0, ie) => {
+                let pe = solana_sdk::program_error::ProgramError::try_from(ie).unwrap();
+                assert!(common::pin_adapter::err::matches_stake_error(
+                    &pe,
+                    solana_sdk::stake::instruction::StakeError::TooSoonToRedelegate
+                ));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+        assert_eq!(after.data, before.data);
+    }
+}
+
+#[tokio::test]
+async fn initial_delegation_requires_minimum() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+
+    // One lamport short of the minimum above the reserve
+    let create_stake = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min - 1, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create_stake, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    match err {
+        solana_sdk::transaction::TransactionError::InstructionError(0, ie) => {
+            let pe = solana_sdk::program_error::ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(
+                &pe,
+                solana_sdk::stake::instruction::StakeError::InsufficientDelegation
+            ));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(SS::deserialize(&acct.data).unwrap(), SS::Initialized(_)));
+
+    // Topped up to exactly reserve + min: the whole excess is delegated
+    transfer(&mut ctx, &stake.pubkey(), 1).await;
+    let ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match SS::deserialize(&acct.data).unwrap() {
+        SS::Stake(_, s, _) => {
+            assert_eq!(u64::from_le_bytes(s.delegation.stake), min);
+            assert_eq!(s.delegation.voter_pubkey, vote.pubkey().to_bytes());
+        }
+        other => panic!("expected Stake, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn delegated_amount_excludes_rent_reserve() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let excess = common::get_minimum_delegation_lamports(&mut ctx).await * 3 + 12_345;
+
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+
+    let create_stake = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + excess, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create_stake, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // The balance is untouched; only the excess over the recorded reserve is delegated
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(acct.lamports, reserve + excess);
+    match SS::deserialize(&acct.data).unwrap() {
+        SS::Stake(meta, s, _) => {
+            let recorded_reserve = u64::from_le_bytes(meta.rent_exempt_reserve);
+            assert_eq!(recorded_reserve, reserve);
+            assert_eq!(u64::from_le_bytes(s.delegation.stake), acct.lamports - recorded_reserve);
+            assert_eq!(u64::from_le_bytes(s.delegation.stake), excess);
+        }
+        other => panic!("expected Stake, got {:?}", other),
+    }
+}
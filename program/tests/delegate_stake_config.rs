@@ -0,0 +1,139 @@
+#![cfg(feature = "enforce-stake-config")]
+//! Delegate account order under `enforce-stake-config`. Requires the SBF
+//! artifact built with the feature:
+//! `cargo-build-sbf --no-default-features --features sbf,enforce-stake-config`
+
+mod common;
+use common::*;
+use common::pin_adapter as ixn;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    message::Message,
+    pubkey::Pubkey,
+    stake::state::Authorized,
+    system_instruction,
+    transaction::TransactionError,
+};
+use std::str::FromStr;
+
+async fn create_dummy_vote_account(ctx: &mut ProgramTestContext, kp: &Keypair) {
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &kp.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &vote_program_id,
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, kp], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+}
+
+// Initialized stake funded with reserve + minimum delegation
+async fn create_funded_stake(ctx: &mut ProgramTestContext, staker: &Keypair) -> Keypair {
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = get_minimum_delegation_lamports(ctx).await;
+
+    let stake = Keypair::new();
+    let withdrawer = Keypair::new();
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    stake
+}
+
+fn delegate_with_config_slot(stake: &Pubkey, vote: &Pubkey, config_slot: Pubkey, staker: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: Pubkey::new_from_array(pinocchio_stake::ID),
+        accounts: vec![
+            AccountMeta::new(*stake, false),
+            AccountMeta::new_readonly(*vote, false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+            AccountMeta::new_readonly(config_slot, false),
+            AccountMeta::new_readonly(*staker, true),
+        ],
+        data: ixn::delegate_stake(stake, staker, vote).data,
+    }
+}
+
+#[tokio::test]
+async fn delegate_accepts_native_order_with_stake_config() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    let staker = Keypair::new();
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+
+    // SDK builder is the canonical native order, config id in slot 4
+    let stake = create_funded_stake(&mut ctx, &staker).await;
+    let ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    assert_eq!(ix.accounts[4].pubkey, Pubkey::new_from_array(pinocchio_stake::state::stake_config::ID));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Repeated stake-history sysvar in the config slot is tolerated
+    let stake = create_funded_stake(&mut ctx, &staker).await;
+    let ix = delegate_with_config_slot(
+        &stake.pubkey(),
+        &vote.pubkey(),
+        solana_sdk::sysvar::stake_history::id(),
+        &staker.pubkey(),
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn delegate_rejects_foreign_account_in_config_slot() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    let staker = Keypair::new();
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+    let stake = create_funded_stake(&mut ctx, &staker).await;
+
+    let ix = delegate_with_config_slot(&stake.pubkey(), &vote.pubkey(), Pubkey::new_unique(), &staker.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    // Staker in slot 4 (config omitted) is not a config account either
+    let ix = Instruction {
+        program_id: Pubkey::new_from_array(pinocchio_stake::ID),
+        accounts: vec![
+            AccountMeta::new(stake.pubkey(), false),
+            AccountMeta::new_readonly(vote.pubkey(), false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+            AccountMeta::new_readonly(staker.pubkey(), true),
+        ],
+        data: ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey()).data,
+    };
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "dispatch-metrics")]
+//! Decode-path markers. Requires the SBF artifact built with the feature:
+//! `cargo-build-sbf --no-default-features --features sbf,dispatch-metrics`
+
+mod common;
+use common::*;
+use solana_sdk::instruction::Instruction;
+
+async fn simulate_logs(ctx: &mut ProgramTestContext, ix: Instruction) -> Vec<String> {
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &[&ctx.payer], ctx.last_blockhash);
+    let sim = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    sim.simulation_details.expect("simulation details").logs
+}
+
+#[tokio::test]
+async fn native_encoding_logs_sbf_manual_path() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    // Canonical 4-byte bincode tag from the SDK builder
+    let ix = common::pin_adapter::get_minimum_delegation();
+    assert_eq!(ix.data, 13u32.to_le_bytes().to_vec());
+    let logs = simulate_logs(&mut ctx, ix).await;
+    assert!(
+        logs.iter().any(|l| l.contains("dispatch:sbf-manual:get_min")),
+        "missing sbf-manual marker: {:?}",
+        logs
+    );
+}
+
+#[tokio::test]
+async fn one_byte_encoding_logs_short_tag_path() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    let mut ix = common::pin_adapter::get_minimum_delegation();
+    ix.data = vec![13u8];
+    let logs = simulate_logs(&mut ctx, ix).await;
+    assert!(
+        logs.iter().any(|l| l.contains("dispatch:short-tag:get_min")),
+        "missing short-tag marker: {:?}",
+        logs
+    );
+}
@@ -1,7 +1,7 @@
 mod common;
 use common::*;
 use common::pin_adapter as ixn;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{message::Message, pubkey::Pubkey, stake::state::Authorized, system_instruction};
 
 #[tokio::test]
 async fn initialize_harness_boots() {
@@ -17,4 +17,41 @@ async fn initialize_harness_boots() {
     assert!(sim.simulation_details.unwrap().return_data.is_some());
 }
 
-// Additional initialize flow tests can be added here.
+// The reserve comes from the Rent sysvar, not the account balance: an over-funded
+// account records the rent minimum and keeps the surplus as free lamports
+#[tokio::test]
+async fn initialize_overfunded_records_rent_minimum_reserve() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let reserve = rent.minimum_balance(SS::ACCOUNT_SIZE);
+    let balance = reserve * 5 + 123;
+
+    let stake = Keypair::new();
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        balance,
+        SS::ACCOUNT_SIZE as u64,
+        &program_id,
+    );
+    let init_ix = ixn::initialize(
+        &stake.pubkey(),
+        &Authorized { staker: Pubkey::new_unique(), withdrawer: Pubkey::new_unique() },
+        &Default::default(),
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(acct.lamports, balance);
+    match SS::deserialize(&acct.data).unwrap() {
+        SS::Initialized(meta) => assert_eq!(u64::from_le_bytes(meta.rent_exempt_reserve), reserve),
+        other => panic!("expected Initialized, got {:?}", other),
+    }
+}
@@ -7,6 +7,7 @@ use solana_sdk::{
     system_instruction,
     stake::state::Authorized,
 };
+use std::str::FromStr;
 
 async fn create_initialized_stake(
     ctx: &mut ProgramTestContext,
@@ -51,6 +52,23 @@ async fn create_initialized_stake(
     kp
 }
 
+async fn create_dummy_vote_account(ctx: &mut ProgramTestContext, kp: &Keypair) {
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &kp.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &vote_program_id,
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, kp], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+}
+
 #[tokio::test]
 async fn merge_inactive_into_inactive_succeeds_and_drains_source() {
     let mut pt = common::program_test();
@@ -151,3 +169,156 @@ async fn merge_authority_mismatch_fails() {
         other => panic!("unexpected banks client error: {:?}", other),
     }
 }
+
+#[tokio::test]
+async fn merge_activation_epoch_pair_drains_source_and_sums_stake() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let dst = create_initialized_stake(&mut ctx, &program_id, &staker, &withdrawer, 2 * min).await;
+    let src = create_initialized_stake(&mut ctx, &program_id, &staker, &withdrawer, min).await;
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+
+    // Delegate both in the same epoch so each is in its activation epoch
+    let msg = Message::new(
+        &[
+            ixn::delegate_stake(&dst.pubkey(), &staker.pubkey(), &vote.pubkey()),
+            ixn::delegate_stake(&src.pubkey(), &staker.pubkey(), &vote.pubkey()),
+        ],
+        Some(&ctx.payer.pubkey()),
+    );
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dst_before = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
+    let src_before = ctx.banks_client.get_account(src.pubkey()).await.unwrap().unwrap();
+
+    let ix = ixn::merge(&dst.pubkey(), &src.pubkey(), &staker.pubkey())
+        .into_iter()
+        .next()
+        .unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let dst_after = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
+    assert_eq!(dst_after.lamports, dst_before.lamports + src_before.lamports);
+    match SS::deserialize(&dst_after.data).unwrap() {
+        SS::Stake(meta, stake, _) => {
+            // AE+AE folds in the source's full balance (reserve + stake)
+            let reserve = u64::from_le_bytes(meta.rent_exempt_reserve);
+            assert_eq!(u64::from_le_bytes(stake.delegation.stake), 2 * min + reserve + min);
+            assert_eq!(dst_after.lamports - reserve, u64::from_le_bytes(stake.delegation.stake));
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+
+    if let Some(src_after) = ctx.banks_client.get_account(src.pubkey()).await.unwrap() {
+        assert_eq!(src_after.lamports, 0);
+        assert!(matches!(SS::deserialize(&src_after.data).unwrap(), SS::Uninitialized));
+    }
+}
+
+#[tokio::test]
+async fn merge_trailing_accounts_do_not_satisfy_staker() {
+    use solana_sdk::instruction::{AccountMeta, InstructionError};
+    use solana_sdk::transaction::TransactionError;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let noise = Keypair::new();
+    let dst = create_initialized_stake(&mut ctx, &program_id, &staker, &withdrawer, 0).await;
+    let src = create_initialized_stake(&mut ctx, &program_id, &staker, &withdrawer, 0).await;
+
+    let base = ixn::merge(&dst.pubkey(), &src.pubkey(), &staker.pubkey())
+        .into_iter()
+        .next()
+        .unwrap();
+
+    // Staker missing, only a trailing noise signer (plus a writable non-signer)
+    let mut ix = base.clone();
+    ix.accounts.retain(|am| am.pubkey != staker.pubkey());
+    ix.accounts.push(AccountMeta::new_readonly(noise.pubkey(), true));
+    ix.accounts.push(AccountMeta::new(Keypair::new().pubkey(), false));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &noise], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    // Same noise after the real staker: merge succeeds and drains the source
+    let mut ix = base;
+    ix.accounts.push(AccountMeta::new_readonly(noise.pubkey(), true));
+    ix.accounts.push(AccountMeta::new(Keypair::new().pubkey(), false));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker, &noise], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let reserve = ctx
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE);
+    let dst_after = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
+    assert_eq!(dst_after.lamports, reserve * 2);
+    if let Some(src_after) = ctx.banks_client.get_account(src.pubkey()).await.unwrap() {
+        assert_eq!(src_after.lamports, 0);
+    }
+}
+
+#[tokio::test]
+async fn merge_different_stakers_same_withdrawer_is_merge_mismatch() {
+    use solana_sdk::{program_error::ProgramError, stake::instruction::StakeError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    // Only the staker differs; the withdrawer is shared
+    let staker_a = Keypair::new();
+    let staker_b = Keypair::new();
+    let withdrawer = Keypair::new();
+
+    let dst = create_initialized_stake(&mut ctx, &program_id, &staker_a, &withdrawer, 0).await;
+    let src = create_initialized_stake(&mut ctx, &program_id, &staker_b, &withdrawer, 0).await;
+    let dst_before = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
+    let src_before = ctx.banks_client.get_account(src.pubkey()).await.unwrap().unwrap();
+
+    // Destination's staker signs, so authorization passes and the meta comparison decides
+    let ix = ixn::merge(&dst.pubkey(), &src.pubkey(), &staker_a.pubkey())
+        .into_iter()
+        .next()
+        .unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker_a], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    match err {
+        TransactionError::InstructionError(0, ie) => {
+            let pe = ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(&pe, StakeError::MergeMismatch), "got {:?}", pe);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // Neither account changed
+    let dst_after = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
+    let src_after = ctx.banks_client.get_account(src.pubkey()).await.unwrap().unwrap();
+    assert_eq!(dst_after.lamports, dst_before.lamports);
+    assert_eq!(dst_after.data, dst_before.data);
+    assert_eq!(src_after.lamports, src_before.lamports);
+    assert_eq!(src_after.data, src_before.data);
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "merge-many")]
+//! MergeMany (tag 240). Requires the SBF artifact built with the feature:
+//! `cargo-build-sbf --no-default-features --features sbf,merge-many`
+
+mod common;
+use common::*;
+use common::pin_adapter as ixn;
+use pinocchio_stake::{instruction::MERGE_MANY_TAG, state::stake_state_v2::StakeStateV2 as SS};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    program_error::ProgramError,
+    stake::{instruction::StakeError, state::Authorized},
+    transaction::TransactionError,
+};
+
+async fn create_initialized(ctx: &mut ProgramTestContext, authorized: &Authorized, withdrawer: &Keypair, extra: u64) -> Keypair {
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let kp = Keypair::new();
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &kp.pubkey(),
+        rent.minimum_balance(space as usize) + extra,
+        space,
+        &program_id,
+    );
+    let init_ix = ixn::initialize_checked(&kp.pubkey(), authorized);
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &kp, withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    kp
+}
+
+fn merge_many_ix(dest: &Pubkey, sources: &[Pubkey], staker: &Pubkey) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*dest, false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+        AccountMeta::new_readonly(solana_sdk::sysvar::stake_history::id(), false),
+    ];
+    accounts.extend(sources.iter().map(|s| AccountMeta::new(*s, false)));
+    accounts.push(AccountMeta::new_readonly(*staker, true));
+    Instruction { program_id: Pubkey::new_from_array(pinocchio_stake::ID), accounts, data: vec![MERGE_MANY_TAG] }
+}
+
+#[tokio::test]
+async fn merge_many_inactive_sources_into_destination() {
+    let mut ctx = common::program_test().start_with_context().await;
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+
+    let dest = create_initialized(&mut ctx, &authorized, &withdrawer, 1_000).await;
+    let mut sources = Vec::new();
+    for extra in [2_000u64, 3_000, 4_000] {
+        sources.push(create_initialized(&mut ctx, &authorized, &withdrawer, extra).await);
+    }
+    let source_keys: Vec<Pubkey> = sources.iter().map(|k| k.pubkey()).collect();
+
+    let mut expected = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap().lamports;
+    for key in &source_keys {
+        expected += ctx.banks_client.get_account(*key).await.unwrap().unwrap().lamports;
+    }
+
+    let ix = merge_many_ix(&dest.pubkey(), &source_keys, &staker.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dst = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    assert_eq!(dst.lamports, expected);
+    assert!(matches!(SS::deserialize(&dst.data).unwrap(), SS::Initialized(_)));
+    for key in &source_keys {
+        // Drained sources are closed by the runtime
+        assert!(ctx.banks_client.get_account(*key).await.unwrap().is_none());
+    }
+}
+
+#[tokio::test]
+async fn merge_many_stops_at_first_incompatible_source() {
+    let mut ctx = common::program_test().start_with_context().await;
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let other_withdrawer = Keypair::new();
+    let mismatched = Authorized { staker: staker.pubkey(), withdrawer: other_withdrawer.pubkey() };
+
+    let dest = create_initialized(&mut ctx, &authorized, &withdrawer, 0).await;
+    let good = create_initialized(&mut ctx, &authorized, &withdrawer, 0).await;
+    let bad = create_initialized(&mut ctx, &mismatched, &other_withdrawer, 0).await;
+
+    let ix = merge_many_ix(&dest.pubkey(), &[good.pubkey(), bad.pubkey()], &staker.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let sim = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    let err = sim.result.unwrap().unwrap_err();
+    match err {
+        TransactionError::InstructionError(0, ie) => {
+            let pe = ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(&pe, StakeError::MergeMismatch));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    let logs = sim.simulation_details.expect("simulation details").logs;
+    assert!(logs.iter().any(|l| l.contains("merge_many:source 1 failed")), "logs: {:?}", logs);
+
+    // The whole instruction failed, so the good source was not merged either
+    assert!(ctx.banks_client.get_account(good.pubkey()).await.unwrap().is_some());
+}
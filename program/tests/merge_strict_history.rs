@@ -0,0 +1,127 @@
+#![cfg(feature = "strict-history")]
+//! Merge classification under `strict-history`: FA+FA merges read the
+//! StakeHistory sysvar and reject a pair that is still warming up, as native
+//! does. Built with `cargo-build-sbf --features sbf,strict-history`.
+
+mod common;
+use common::*;
+use common::pin_adapter as ixn;
+use solana_sdk::{
+    message::Message,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    stake::{instruction::StakeError, state::Authorized},
+    stake_history::{StakeHistory, StakeHistoryEntry},
+    system_instruction,
+    transaction::TransactionError,
+};
+use std::str::FromStr;
+
+use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+
+async fn create_dummy_vote_account(ctx: &mut ProgramTestContext, kp: &Keypair) {
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &kp.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &vote_program_id,
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, kp], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+}
+
+// Two accounts with the same authorities, delegated to `vote` this epoch
+async fn delegated_pair(ctx: &mut ProgramTestContext, staker: &Keypair, vote: &Pubkey, stake: u64) -> (Keypair, Keypair) {
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+    let reserve = ctx.banks_client.get_rent().await.unwrap().minimum_balance(SS::ACCOUNT_SIZE);
+    let withdrawer = Keypair::new();
+    let (dst, src) = (Keypair::new(), Keypair::new());
+    for kp in [&dst, &src] {
+        let create = system_instruction::create_account(
+            &ctx.payer.pubkey(),
+            &kp.pubkey(),
+            reserve + stake,
+            SS::ACCOUNT_SIZE as u64,
+            &program_id,
+        );
+        let init_ix = ixn::initialize_checked(
+            &kp.pubkey(),
+            &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+        );
+        let del_ix = ixn::delegate_stake(&kp.pubkey(), &staker.pubkey(), vote);
+        let msg = Message::new(&[create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, kp, &withdrawer, staker], ctx.last_blockhash).unwrap();
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+    }
+    (dst, src)
+}
+
+// Replace the cluster history with a single record for `epoch`
+fn set_history(ctx: &mut ProgramTestContext, epoch: u64, effective: u64, activating: u64) {
+    let mut history = StakeHistory::default();
+    history.add(epoch, StakeHistoryEntry { effective, activating, deactivating: 0 });
+    ctx.set_sysvar(&history);
+}
+
+async fn merge(ctx: &mut ProgramTestContext, dst: &Keypair, src: &Keypair, staker: &Keypair) -> Result<(), TransactionError> {
+    let ix = ixn::merge(&dst.pubkey(), &src.pubkey(), &staker.pubkey()).into_iter().next().unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.map_err(|e| e.unwrap())
+}
+
+#[tokio::test]
+async fn merge_partially_warmed_pair_is_merge_mismatch() {
+    let mut ctx = common::program_test().start_with_context().await;
+    let staker = Keypair::new();
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+
+    let stake = get_minimum_delegation_lamports(&mut ctx).await.max(1_000_000);
+    let activation_epoch = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap().epoch;
+    let (dst, src) = delegated_pair(&mut ctx, &staker, &vote.pubkey(), stake).await;
+    warp_epochs(&mut ctx, 1).await;
+
+    // Cluster warms 9% of 10x against 10x activating: each account is ~9% effective
+    set_history(&mut ctx, activation_epoch, stake * 10, stake * 10);
+
+    let err = merge(&mut ctx, &dst, &src, &staker).await.unwrap_err();
+    match err {
+        TransactionError::InstructionError(0, ie) => {
+            let pe = ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(&pe, StakeError::MergeMismatch));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn merge_fully_warmed_pair_succeeds() {
+    let mut ctx = common::program_test().start_with_context().await;
+    let staker = Keypair::new();
+    let vote = Keypair::new();
+    create_dummy_vote_account(&mut ctx, &vote).await;
+
+    let stake = get_minimum_delegation_lamports(&mut ctx).await.max(1_000_000);
+    let activation_epoch = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap().epoch;
+    let (dst, src) = delegated_pair(&mut ctx, &staker, &vote.pubkey(), stake).await;
+    warp_epochs(&mut ctx, 1).await;
+
+    // Cluster effective stake dwarfs the activating total: both are fully active
+    set_history(&mut ctx, activation_epoch, stake * 1_000, stake * 2);
+
+    merge(&mut ctx, &dst, &src, &staker).await.unwrap();
+    let dst_after = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
+    match SS::deserialize(&dst_after.data).unwrap() {
+        SS::Stake(_, s, _) => assert_eq!(u64::from_le_bytes(s.delegation.stake), stake * 2),
+        other => panic!("expected Stake, got {:?}", other),
+    }
+}
@@ -6,13 +6,6 @@ use solana_sdk::{
     vote::{instruction as vote_instruction, state::{VoteInit, VoteStateV3, VoteStateVersions}},
 };
 
-async fn warp_one_epoch(ctx: &mut ProgramTestContext) {
-    refresh_blockhash(ctx).await;
-    let root_slot = ctx.banks_client.get_root_slot().await.unwrap();
-    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
-    ctx.warp_to_slot(root_slot + slots_per_epoch).unwrap();
-}
-
 async fn create_vote(ctx: &mut ProgramTestContext, node: &Keypair, voter: &Pubkey, withdrawer: &Pubkey, vote_account: &Keypair) {
     let rent = ctx.banks_client.get_rent().await.unwrap();
     let mut ixs = vec![system_instruction::create_account(&ctx.payer.pubkey(), &node.pubkey(), rent.minimum_balance(0), 0, &solana_sdk::system_program::id())];
@@ -103,7 +96,7 @@ async fn move_stake_to_inactive_conserves_lamports_and_stake() {
     ctx.banks_client.process_transaction(tx).await.unwrap();
 
     // Activate (warp one epoch)
-    warp_one_epoch(&mut ctx).await;
+    common::warp_epochs(&mut ctx, 1).await;
 
     // Pre balances
     let pre_src = ctx.banks_client.get_account(src.pubkey()).await.unwrap().unwrap().lamports;
@@ -180,7 +173,7 @@ async fn move_stake_active_to_active_same_voter_conserves_totals() {
         let tx = Transaction::new_signed_with_payer(&[del_ix], Some(&ctx.payer.pubkey()), &[&ctx.payer, &staker], ctx.last_blockhash);
         ctx.banks_client.process_transaction(tx).await.unwrap();
     }
-    warp_one_epoch(&mut ctx).await;
+    common::warp_epochs(&mut ctx, 1).await;
 
     let pre_src_acc = ctx.banks_client.get_account(src.pubkey()).await.unwrap().unwrap();
     let pre_dst_acc = ctx.banks_client.get_account(dst.pubkey()).await.unwrap().unwrap();
@@ -183,3 +183,150 @@ async fn move_lamports_fails_if_staker_not_third_account() {
     let res = ctx.banks_client.process_transaction(tx).await;
     assert!(res.is_err(), "MoveLamports must fail if staker not third: {:?}", res);
 }
+
+#[tokio::test]
+async fn short_encoded_moves_dispatch() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = Keypair::new();
+    let dest = Keypair::new();
+    let auth = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let extra: u64 = 2_000_000;
+    let msg = Message::new(
+        &[
+            system_instruction::create_account(&ctx.payer.pubkey(), &source.pubkey(), reserve + extra, space, &program_id),
+            system_instruction::create_account(&ctx.payer.pubkey(), &dest.pubkey(), reserve, space, &program_id),
+            ixn::initialize_checked(&source.pubkey(), &auth),
+            ixn::initialize_checked(&dest.pubkey(), &auth),
+        ],
+        Some(&ctx.payer.pubkey()),
+    );
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &source, &dest, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let short_ix = |data: Vec<u8>| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(source.pubkey(), false),
+            AccountMeta::new(dest.pubkey(), false),
+            AccountMeta::new_readonly(staker.pubkey(), true),
+        ],
+        data,
+    };
+
+    // [17, lamports LE] moves the amount
+    let amount: u64 = 750_000;
+    let mut data = vec![17u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    let msg = Message::new(&[short_ix(data)], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let src = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    let dst = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    assert_eq!(src.lamports, reserve + extra - amount);
+    assert_eq!(dst.lamports, reserve + amount);
+
+    // 1-byte forms default to zero lamports, which both handlers reject
+    for tag in [16u8, 17u8] {
+        refresh_blockhash(&mut ctx).await;
+        let msg = Message::new(&[short_ix(vec![tag])], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(
+            err,
+            solana_sdk::transaction::TransactionError::InstructionError(
+                0,
+                solana_sdk::instruction::InstructionError::InvalidArgument
+            ),
+            "tag {tag}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn move_lamports_rejects_wrong_sized_source() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = Keypair::new();
+    let dest = Keypair::new();
+    let auth = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    for kp in [&source, &dest] {
+        let create = system_instruction::create_account(&ctx.payer.pubkey(), &kp.pubkey(), reserve * 2, space, &program_id);
+        let init_ix = ixn::initialize_checked(&kp.pubkey(), &auth);
+        let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, kp, &withdrawer], ctx.last_blockhash).unwrap();
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    // Same Initialized state, 8 bytes longer than the canonical layout
+    let mut src = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    src.data.extend_from_slice(&[0u8; 8]);
+    ctx.set_account(&source.pubkey(), &src.into());
+
+    let ix = ixn::move_lamports(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), 1);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+}
+
+// Source and destination are the same account: rejected as an invalid argument
+// before any state is read, and the balance is untouched
+#[tokio::test]
+async fn move_lamports_into_itself_fails() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    // Initialized with free lamports above the reserve
+    let stake = Keypair::new();
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + 1_000_000, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let ix = ixn::move_lamports(&stake.pubkey(), &stake.pubkey(), &staker.pubkey(), 1_000);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve + 1_000_000);
+}
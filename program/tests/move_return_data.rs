@@ -0,0 +1,80 @@
+#![cfg(feature = "move-return-data")]
+//! MoveLamports/MoveStake return data. Requires the SBF artifact built with the feature:
+//! `cargo-build-sbf --no-default-features --features sbf,move-return-data`
+
+mod common;
+use common::*;
+use common::pin_adapter as ixn;
+use solana_program_test::processor;
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    program::{get_return_data, invoke},
+    program_error::ProgramError,
+    stake::state::Authorized,
+};
+
+// Caller accounts: [stake_program, source, destination, staker]; data: u64 LE amount.
+// CPIs MoveLamports and fails unless the stake program returns exactly that amount.
+fn move_lamports_caller(_program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    let [stake_program, source, destination, staker] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let amount = u64::from_le_bytes(input.try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+    let ix = ixn::move_lamports(source.key, destination.key, staker.key, amount);
+    invoke(&ix, &[source.clone(), destination.clone(), staker.clone(), stake_program.clone()])?;
+
+    match get_return_data() {
+        Some((program_id, data)) if program_id == *stake_program.key && data == amount.to_le_bytes() => Ok(()),
+        _ => Err(ProgramError::Custom(1)),
+    }
+}
+
+#[tokio::test]
+async fn move_lamports_cpi_reads_moved_amount() {
+    let caller_id = Pubkey::new_unique();
+    let mut pt = common::program_test();
+    pt.add_builtin_program("move_lamports_caller", caller_id, processor!(move_lamports_caller));
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let free: u64 = 1_000_000;
+
+    let source = Keypair::new();
+    let dest = Keypair::new();
+    let auth = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    for (kp, lamports) in [(&source, reserve + free), (&dest, reserve)] {
+        let create = system_instruction::create_account(&ctx.payer.pubkey(), &kp.pubkey(), lamports, space, &program_id);
+        let init_ix = ixn::initialize_checked(&kp.pubkey(), &auth);
+        let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, kp, &withdrawer], ctx.last_blockhash).unwrap();
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    let amount = free / 2;
+    let ix = Instruction {
+        program_id: caller_id,
+        accounts: vec![
+            AccountMeta::new_readonly(program_id, false),
+            AccountMeta::new(source.pubkey(), false),
+            AccountMeta::new(dest.pubkey(), false),
+            AccountMeta::new_readonly(staker.pubkey(), true),
+        ],
+        data: amount.to_le_bytes().to_vec(),
+    };
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dst = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    assert_eq!(dst.lamports, reserve + amount);
+}
@@ -310,3 +310,286 @@ async fn move_stake_zero_amount_fails() {
         other => panic!("unexpected banks client error: {:?}", other),
     }
 }
+
+#[tokio::test]
+async fn move_stake_destination_gets_union_of_flags() {
+    use pinocchio_stake::state::{stake_state_v2::StakeStateV2 as SS, StakeFlags};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let vote = Keypair::new();
+    create_vote_like_account(&mut ctx, &vote).await;
+
+    let minimum = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let vote_pk = vote.pubkey();
+    let source = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote_pk, minimum * 3).await;
+
+    // Initialized destination carries no flags
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let dest = Keypair::new();
+    let create_dest = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &dest.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &program_id,
+    );
+    let init_dest = ixn::initialize_checked(
+        &dest.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create_dest, init_dest], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &dest, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
+    let mut root_slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for _ in 0..64 {
+        root_slot += slots_per_epoch;
+        ctx.warp_to_slot(root_slot).unwrap();
+    }
+    refresh_blockhash(&mut ctx).await;
+
+    // Set a flag on the fully active source only
+    let must = StakeFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED;
+    let mut src_acc = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    let SS::Stake(meta, stake, _) = SS::deserialize(&src_acc.data).unwrap() else {
+        panic!("source should be Stake");
+    };
+    src_acc.data = ixn::encode_program_stake_state(&SS::Stake(meta, stake, must));
+    ctx.set_account(&source.pubkey(), &src_acc.into());
+
+    let ix = ixn::move_stake(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), minimum);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dst_after = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    match SS::deserialize(&dst_after.data).unwrap() {
+        SS::Stake(_, s, flags) => {
+            assert_eq!(u64::from_le_bytes(s.delegation.stake), minimum);
+            assert_eq!(flags, StakeFlags::empty().union(must));
+        }
+        other => panic!("destination should be Stake after move: {:?}", other),
+    }
+    // Source keeps its own flags
+    let src_after = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(SS::deserialize(&src_after.data).unwrap(), SS::Stake(_, _, f) if f == must));
+}
+
+#[tokio::test]
+async fn move_stake_inactive_destination_requires_minimum_delegation() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::{instruction::InstructionError, program_error::ProgramError, stake::instruction::StakeError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let vote = Keypair::new();
+    create_vote_like_account(&mut ctx, &vote).await;
+
+    let minimum = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let vote_pk = vote.pubkey();
+    let source = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote_pk, minimum * 3).await;
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let dest = Keypair::new();
+    let create_dest = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &dest.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &program_id,
+    );
+    let init_dest = ixn::initialize_checked(
+        &dest.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create_dest, init_dest], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &dest, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
+    let mut root_slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for _ in 0..64 {
+        root_slot += slots_per_epoch;
+        ctx.warp_to_slot(root_slot).unwrap();
+    }
+    refresh_blockhash(&mut ctx).await;
+
+    // One lamport short of the minimum (a zero move is rejected earlier as InvalidArgument)
+    if minimum > 1 {
+        let ix = ixn::move_stake(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), minimum - 1);
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        match err {
+            TransactionError::InstructionError(0, ie) => {
+                let pe = ProgramError::try_from(ie).unwrap();
+                assert!(common::pin_adapter::err::matches_stake_error(&pe, StakeError::InsufficientDelegation));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    } else {
+        let ix = ixn::move_stake(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), 0);
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+    }
+    let dst = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(SS::deserialize(&dst.data).unwrap(), SS::Initialized(_)));
+
+    // Exactly the minimum delegates the destination
+    let ix = ixn::move_stake(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), minimum);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dst = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    match SS::deserialize(&dst.data).unwrap() {
+        SS::Stake(_, s, _) => assert_eq!(u64::from_le_bytes(s.delegation.stake), minimum),
+        other => panic!("destination should be Stake after move: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn move_stake_active_to_active_weights_credits_observed() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let vote = Keypair::new();
+    create_vote_like_account(&mut ctx, &vote).await;
+
+    let vote_pk = vote.pubkey();
+    let source = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote_pk, 3_000_000).await;
+    let dest = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote_pk, 1_000_000).await;
+
+    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
+    let mut root_slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for _ in 0..64 {
+        root_slot += slots_per_epoch;
+        ctx.warp_to_slot(root_slot).unwrap();
+    }
+    refresh_blockhash(&mut ctx).await;
+
+    // Give the two delegations different credits_observed so the move must reconcile them
+    let (src_credits, dst_credits) = (100u64, 40u64);
+    for (key, credits) in [(source.pubkey(), src_credits), (dest.pubkey(), dst_credits)] {
+        let mut acc = ctx.banks_client.get_account(key).await.unwrap().unwrap();
+        let SS::Stake(meta, mut stake, flags) = SS::deserialize(&acc.data).unwrap() else {
+            panic!("expected Stake");
+        };
+        stake.credits_observed = credits.to_le_bytes();
+        acc.data = ixn::encode_program_stake_state(&SS::Stake(meta, stake, flags));
+        ctx.set_account(&key, &acc.into());
+    }
+
+    let src_before = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    let dst_before = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    let stake_of = |data: &[u8]| match SS::deserialize(data).unwrap() {
+        SS::Stake(_, s, _) => (u64::from_le_bytes(s.delegation.stake), u64::from_le_bytes(s.credits_observed)),
+        other => panic!("expected Stake, got {:?}", other),
+    };
+    let (src_stake, _) = stake_of(&src_before.data);
+    let (dst_stake, _) = stake_of(&dst_before.data);
+
+    let amount = 1_000_000u64;
+    let ix = ixn::move_stake(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), amount);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let src_after = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    let dst_after = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    let (src_stake_after, src_credits_after) = stake_of(&src_after.data);
+    let (dst_stake_after, dst_credits_after) = stake_of(&dst_after.data);
+
+    // Destination credits are the stake-weighted average, rounded up like a merge
+    let total = u128::from(dst_stake + amount);
+    let weighted = u128::from(dst_credits) * u128::from(dst_stake) + u128::from(src_credits) * u128::from(amount);
+    let expected = ((weighted + total - 1) / total) as u64;
+    assert_eq!(dst_credits_after, expected);
+    assert!(dst_credits < dst_credits_after && dst_credits_after <= src_credits);
+    // Source keeps its own credits
+    assert_eq!(src_credits_after, src_credits);
+
+    // No stake or lamports created or destroyed
+    assert_eq!(src_stake_after + dst_stake_after, src_stake + dst_stake);
+    assert_eq!(src_after.lamports + dst_after.lamports, src_before.lamports + dst_before.lamports);
+}
+
+#[tokio::test]
+async fn move_stake_rejects_wrong_sized_source() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let vote = Keypair::new();
+    create_vote_like_account(&mut ctx, &vote).await;
+    let source = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote.pubkey(), 0).await;
+    let dest = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote.pubkey(), 0).await;
+    common::warp_epochs(&mut ctx, 4).await;
+
+    // Same delegated state, 8 bytes longer than the canonical layout
+    let mut src = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    src.data.extend_from_slice(&[0u8; 8]);
+    ctx.set_account(&source.pubkey(), &src.into());
+
+    let minimum = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let ix = ixn::move_stake(&source.pubkey(), &dest.pubkey(), &staker.pubkey(), minimum);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+}
+
+// Source and destination are the same account: rejected as an invalid argument
+// before any state is read, and the balance is untouched
+#[tokio::test]
+async fn move_stake_into_itself_fails() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let vote = Keypair::new();
+    create_vote_like_account(&mut ctx, &vote).await;
+    let stake = setup_active_stake(&mut ctx, &program_id, &staker, &withdrawer, &vote.pubkey(), 1_000_000).await;
+    let before = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+
+    let ix = ixn::move_stake(&stake.pubkey(), &stake.pubkey(), &staker.pubkey(), 1);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, before.lamports);
+    assert_eq!(after.data, before.data);
+}
@@ -0,0 +1,30 @@
+mod common;
+use common::*;
+use common::pin_adapter as ixn;
+use solana_sdk::{
+    instruction::InstructionError,
+    message::Message,
+    pubkey::Pubkey,
+    transaction::TransactionError,
+};
+
+// Redelegate is deprecated and never enabled natively: rejected with native's
+// InvalidInstructionData rather than a custom code, so clients see the same error
+#[tokio::test]
+async fn redelegate_is_rejected_as_invalid_instruction_data() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    let staker = Keypair::new();
+    let ix = ixn::redelegate(
+        &Pubkey::new_unique(),
+        &staker.pubkey(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+    );
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidInstructionData));
+}
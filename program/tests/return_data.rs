@@ -20,3 +20,112 @@ async fn get_minimum_delegation_returns_8_le_bytes() {
     let val = u64::from_le_bytes(buf);
     assert!(val > 0);
 }
+
+#[tokio::test]
+async fn get_minimum_delegation_matches_enforced_minimum() {
+    use crate::common::pin_adapter as ixn;
+    use solana_sdk::{message::Message, pubkey::Pubkey, stake::state::Authorized, system_instruction};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    // Decode the instruction's return data and compare with the helper
+    let ix = ixn::get_minimum_delegation();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &[&ctx.payer], ctx.last_blockhash);
+    let sim = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    let rd = sim.simulation_details.unwrap().return_data.expect("no return data");
+    let min = u64::from_le_bytes(rd.data.as_slice().try_into().expect("8 bytes"));
+    assert_eq!(min, pinocchio_stake::helpers::get_minimum_delegation());
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::new_from_array(pinocchio_stake::state::vote_state::ID);
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space as usize),
+        vote_space,
+        &vote_program_id,
+    );
+    let msg = Message::new(&[create_vote], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &vote], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Stake funded one lamport short of the reported minimum
+    let stake = Keypair::new();
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min - 1, space, &program_id);
+    let init = ixn::initialize_checked(&stake.pubkey(), &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() });
+    let msg = Message::new(&[create, init], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let del = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[del.clone()], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    match err {
+        solana_sdk::transaction::TransactionError::InstructionError(_, ie) => {
+            let pe = solana_sdk::program_error::ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(
+                &pe,
+                solana_sdk::stake::instruction::StakeError::InsufficientDelegation
+            ));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // Exactly the minimum delegates; top up to 2x so a split of exactly `min` fits both sides
+    common::transfer(&mut ctx, &stake.pubkey(), min + 1).await;
+    refresh_blockhash(&mut ctx).await;
+    let msg = Message::new(&[del], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dest = Keypair::new();
+    let create_dest = system_instruction::create_account(&ctx.payer.pubkey(), &dest.pubkey(), reserve, space, &program_id);
+    let split_ix = ixn::split(&stake.pubkey(), &staker.pubkey(), min, &dest.pubkey()).into_iter().last().unwrap();
+    let msg = Message::new(&[create_dest, split_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &dest, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    for (key, expected) in [(stake.pubkey(), min), (dest.pubkey(), min)] {
+        let acct = ctx.banks_client.get_account(key).await.unwrap().unwrap();
+        match SS::deserialize(&acct.data).unwrap() {
+            SS::Stake(_, s, _) => assert_eq!(u64::from_le_bytes(s.delegation.stake), expected),
+            other => panic!("expected Stake state, got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn get_minimum_delegation_ignores_stray_accounts() {
+    use crate::common::pin_adapter as ixn;
+    use solana_sdk::instruction::AccountMeta;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+
+    // Native takes zero accounts but does not reject extras; neither do we
+    let mut ix = ixn::get_minimum_delegation();
+    ix.accounts = vec![
+        AccountMeta::new_readonly(solana_sdk::sysvar::clock::id(), false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+    ];
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &[&ctx.payer], ctx.last_blockhash);
+    let sim = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    assert!(sim.result.unwrap().is_ok());
+    let rd = sim.simulation_details.unwrap().return_data.expect("no return data");
+    let min = u64::from_le_bytes(rd.data.as_slice().try_into().expect("8 bytes"));
+    assert_eq!(min, pinocchio_stake::helpers::get_minimum_delegation());
+}
@@ -190,3 +190,111 @@ async fn set_lockup_checked_custodian_in_force() {
     }
     assert_eq!(meta.lockup.unix_timestamp, new_ts);
 }
+
+// SetLockup on a delegated (Stake) account: lockup persists and the delegation is untouched
+#[tokio::test]
+async fn set_lockup_on_delegated_stake() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>();
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space),
+        vote_space as u64,
+        &solana_sdk::vote::program::id(),
+    );
+
+    let stake_acc = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let minimum = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake_acc.pubkey(),
+        rent.minimum_balance(space as usize) + minimum,
+        space,
+        &program_id,
+    );
+    let init_ix = ixn::initialize_checked(
+        &stake_acc.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake_acc.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create_vote, create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &vote, &stake_acc, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let before = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    let SS::Stake(_, stake_before, flags_before) = SS::deserialize(&before.data).unwrap() else {
+        panic!("expected Stake after delegate");
+    };
+
+    // Lockup not in force: the withdrawer alone may set it
+    let custodian = Pubkey::new_unique();
+    let args = LockupArgs { unix_timestamp: Some(1_000), epoch: Some(50), custodian: Some(custodian) };
+    let mut ix = solana_sdk::stake::instruction::set_lockup(&stake_acc.pubkey(), &args, &withdrawer.pubkey());
+    ix.program_id = program_id;
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, before.lamports);
+    match SS::deserialize(&after.data).unwrap() {
+        SS::Stake(meta, stake, flags) => {
+            assert_eq!(meta.lockup.unix_timestamp, 1_000);
+            assert_eq!(meta.lockup.epoch, 50);
+            assert_eq!(meta.lockup.custodian, custodian.to_bytes());
+            assert_eq!(stake, stake_before);
+            assert_eq!(flags, flags_before);
+        }
+        other => panic!("expected Stake after set_lockup, got {:?}", other),
+    }
+}
+
+// Lockups live in Meta: an Uninitialized account has none to set, whoever signs
+#[tokio::test]
+async fn set_lockup_on_uninitialized_account_fails() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake_acc.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &program_id,
+    );
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let signer = Keypair::new();
+    let args = LockupArgs { unix_timestamp: Some(1_000), epoch: Some(50), custodian: Some(Pubkey::new_unique()) };
+    let mut ix = solana_sdk::stake::instruction::set_lockup(&stake_acc.pubkey(), &args, &signer.pubkey());
+    ix.program_id = program_id;
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &signer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+
+    let acct = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(SS::deserialize(&acct.data).unwrap(), SS::Uninitialized));
+}
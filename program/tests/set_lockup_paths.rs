@@ -139,3 +139,228 @@ async fn set_lockup_checked_signer_roles() {
         }
     }
 }
+
+// SetLockupChecked with no fields: native still authorizes the signer role, then changes nothing.
+#[tokio::test]
+async fn set_lockup_checked_empty_args_requires_role_signer() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let custodian = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let lockup = Lockup { unix_timestamp: 0, epoch: 0, custodian: custodian.pubkey() };
+    let stake = create_initialized_stake(&mut ctx, &program_id, &authorized, &lockup).await;
+    let before = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+
+    let empty = solana_sdk::stake::instruction::LockupArgs { unix_timestamp: None, epoch: None, custodian: None };
+
+    // Wrong signer (staker) while lockup is not in force -> rejected
+    let ix = ixn::set_lockup_checked(&stake.pubkey(), &empty, &staker.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::MissingRequiredSignature
+        )
+    );
+
+    // Withdrawer signs -> accepted, lockup untouched
+    refresh_blockhash(&mut ctx).await;
+    let ix = ixn::set_lockup_checked(&stake.pubkey(), &empty, &withdrawer.pubkey());
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(before.data, after.data, "empty SetLockupChecked must not change state");
+}
+
+#[tokio::test]
+async fn set_lockup_readonly_stake_rejected_early() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let stake = create_initialized_stake(&mut ctx, &program_id, &authorized, &Lockup::default()).await;
+
+    let args = solana_sdk::stake::instruction::LockupArgs { unix_timestamp: None, epoch: Some(1), custodian: None };
+    let mut ix = solana_sdk::stake::instruction::set_lockup(&stake.pubkey(), &args, &withdrawer.pubkey());
+    ix.accounts[0].is_writable = false;
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::InvalidInstructionData
+        )
+    );
+
+    // State untouched
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    let (meta, _, _) = common::pin_adapter::get_stake_account(&mut ctx.banks_client, &stake.pubkey()).await;
+    assert_eq!(meta.lockup, Lockup::default());
+    assert_eq!(acct.owner, program_id);
+}
+
+#[tokio::test]
+async fn set_lockup_checked_rejects_payload_custodian() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let new_custodian = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let stake = create_initialized_stake(&mut ctx, &program_id, &authorized, &Lockup::default()).await;
+
+    // Well-formed payload carrying flags 0x02|0x04: epoch plus an embedded custodian
+    let args = solana_sdk::stake::instruction::LockupArgs {
+        unix_timestamp: None,
+        epoch: Some(7),
+        custodian: Some(new_custodian.pubkey()),
+    };
+    let ix = ixn::set_lockup_checked(&stake.pubkey(), &args, &withdrawer.pubkey());
+    assert_eq!(ix.data[..2], [12u8, 0x06]);
+    assert_eq!(ix.data.len(), 2 + 8 + 32);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer, &new_custodian], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::InvalidInstructionData
+        )
+    );
+
+    // Lockup untouched
+    let (meta, _, _) = common::pin_adapter::get_stake_account(&mut ctx.banks_client, &stake.pubkey()).await;
+    assert_eq!(meta.lockup, Lockup::default());
+}
+
+// The checked handler accepts only 0x01|0x02; bits the unchecked mask (0x07) allows are still refused
+#[tokio::test]
+async fn set_lockup_checked_handler_rejects_flags_outside_0x03() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let stake = create_initialized_stake(&mut ctx, &program_id, &authorized, &Lockup::default()).await;
+
+    for flags in [0x04u8, 0x08] {
+        let ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(stake.pubkey(), false),
+                AccountMeta::new_readonly(withdrawer.pubkey(), true),
+            ],
+            data: vec![12u8, flags],
+        };
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(
+            err,
+            solana_sdk::transaction::TransactionError::InstructionError(
+                0,
+                solana_sdk::instruction::InstructionError::InvalidInstructionData
+            ),
+            "flags {:#04x}",
+            flags
+        );
+        refresh_blockhash(&mut ctx).await;
+    }
+
+    let (meta, _, _) = common::pin_adapter::get_stake_account(&mut ctx.banks_client, &stake.pubkey()).await;
+    assert_eq!(meta.lockup, Lockup::default());
+}
+
+// Ceiling for a short-tag SetLockupChecked on an Initialized account. The pre-dispatch
+// role check used to pay a second `get_stake_state` and `Clock::get` (plus the fallback
+// signer scan) on top of the handler's own load; the bound leaves no room for them.
+const SLC_SHORT_CU_BUDGET: u64 = 2_500;
+
+#[tokio::test]
+async fn set_lockup_checked_short_path_loads_state_once() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let lockup = Lockup { unix_timestamp: 0, epoch: 0, custodian: Pubkey::new_unique() };
+    let stake = create_initialized_stake(&mut ctx, &program_id, &authorized, &lockup).await;
+
+    let args = solana_sdk::stake::instruction::LockupArgs { unix_timestamp: Some(1), epoch: None, custodian: None };
+    let ix = ixn::set_lockup_checked(&stake.pubkey(), &args, &withdrawer.pubkey());
+    assert_eq!(ix.data[0], 12);
+
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let sim = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    assert!(matches!(sim.result, Some(Ok(()))), "simulation failed: {:?}", sim.result);
+    let units = sim.simulation_details.expect("simulation details").units_consumed;
+    assert!(units <= SLC_SHORT_CU_BUDGET, "short-tag SetLockupChecked {} CU > budget {} CU", units, SLC_SHORT_CU_BUDGET);
+}
+
+// Short-tag SetLockupChecked on an Uninitialized account fails on state, not on
+// whichever signer happens to be present, and leaves the account untouched
+#[cfg(not(feature = "strict-wire"))]
+#[tokio::test]
+async fn set_lockup_checked_short_path_rejects_uninitialized() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        rent.minimum_balance(space as usize),
+        space,
+        &program_id,
+    );
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let signer = Keypair::new();
+    let args = solana_sdk::stake::instruction::LockupArgs { unix_timestamp: Some(1), epoch: Some(2), custodian: None };
+    let ix = ixn::set_lockup_checked(&stake.pubkey(), &args, &signer.pubkey());
+    assert_eq!(ix.data[0], 12);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &signer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(SS::deserialize(&acct.data).unwrap(), SS::Uninitialized));
+}
@@ -0,0 +1,526 @@
+mod common;
+use common::*;
+use common::pin_adapter as ixn;
+use solana_sdk::{
+    message::Message,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+// Create a program-owned, zeroed (Uninitialized) stake-sized account holding `lamports`
+async fn create_uninitialized_stake(ctx: &mut ProgramTestContext, program_id: &Pubkey, lamports: u64) -> Keypair {
+    let kp = Keypair::new();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &kp.pubkey(), lamports, space, program_id);
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &kp], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    kp
+}
+
+#[tokio::test]
+async fn split_uninitialized_source_partial_moves_lamports_only() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 3).await;
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+
+    // Source signs as its own authority: [source, dest, source]
+    let amount = reserve;
+    let ix = ixn::split(&source.pubkey(), &source.pubkey(), amount, &dest.pubkey())
+        .into_iter()
+        .last()
+        .unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &source], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let src_after = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    let dst_after = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    assert_eq!(src_after.lamports, reserve * 2);
+    assert_eq!(dst_after.lamports, reserve * 2);
+
+    // No state is written on either side
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    assert!(matches!(SS::deserialize(&src_after.data).unwrap(), SS::Uninitialized));
+    assert!(matches!(SS::deserialize(&dst_after.data).unwrap(), SS::Uninitialized));
+    assert!(src_after.data.iter().all(|b| *b == 0));
+    assert!(dst_after.data.iter().all(|b| *b == 0));
+}
+
+#[tokio::test]
+async fn split_uninitialized_source_full_drain() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 2).await;
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+
+    let full = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap().lamports;
+    let ix = ixn::split(&source.pubkey(), &source.pubkey(), full, &dest.pubkey())
+        .into_iter()
+        .last()
+        .unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &source], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dst_after = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    assert_eq!(dst_after.lamports, reserve + full);
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    assert!(matches!(SS::deserialize(&dst_after.data).unwrap(), SS::Uninitialized));
+
+    // Source may be purged by the runtime once it reaches zero lamports
+    if let Some(src_after) = ctx.banks_client.get_account(source.pubkey()).await.unwrap() {
+        assert_eq!(src_after.lamports, 0);
+        assert!(matches!(SS::deserialize(&src_after.data).unwrap(), SS::Uninitialized));
+    }
+}
+
+#[tokio::test]
+async fn split_uninitialized_source_without_source_signature_fails() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 2).await;
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+    let other = Keypair::new();
+
+    let ix = ixn::split(&source.pubkey(), &other.pubkey(), reserve, &dest.pubkey())
+        .into_iter()
+        .last()
+        .unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &other], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::MissingRequiredSignature
+        )
+    );
+}
+
+#[tokio::test]
+async fn split_destination_owner_and_size_fail_distinctly() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 3).await;
+
+    // Stake-sized destination owned by the system program
+    let system_owned = Keypair::new();
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &system_owned.pubkey(),
+        reserve,
+        space,
+        &solana_sdk::system_program::id(),
+    );
+    // Program-owned destination with the wrong size
+    let short = Keypair::new();
+    let create_short = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &short.pubkey(),
+        rent.minimum_balance(space as usize - 1),
+        space - 1,
+        &program_id,
+    );
+    let msg = Message::new(&[create, create_short], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &system_owned, &short], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    for (dest, expected) in [
+        (system_owned.pubkey(), solana_sdk::instruction::InstructionError::InvalidAccountOwner),
+        (short.pubkey(), solana_sdk::instruction::InstructionError::InvalidAccountData),
+    ] {
+        let ix = ixn::split(&source.pubkey(), &source.pubkey(), reserve, &dest)
+            .into_iter()
+            .last()
+            .unwrap();
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &source], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(
+            err,
+            solana_sdk::transaction::TransactionError::InstructionError(0, expected)
+        );
+    }
+
+    // No lamports moved on either failure
+    let src_after = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    assert_eq!(src_after.lamports, reserve * 3);
+    let sys_after = ctx.banks_client.get_account(system_owned.pubkey()).await.unwrap().unwrap();
+    assert_eq!(sys_after.lamports, reserve);
+    let short_after = ctx.banks_client.get_account(short.pubkey()).await.unwrap().unwrap();
+    assert_eq!(short_after.lamports, rent.minimum_balance(space as usize - 1));
+}
+
+#[tokio::test]
+async fn split_uninitialized_source_into_itself_fails() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 2).await;
+
+    let ix = ixn::split(&source.pubkey(), &source.pubkey(), reserve, &source.pubkey())
+        .into_iter()
+        .last()
+        .unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &source], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::InvalidArgument
+        )
+    );
+
+    let after = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve * 2);
+}
+
+#[tokio::test]
+async fn split_destination_errors_precede_over_balance() {
+    use solana_sdk::{instruction::InstructionError, stake::state::Authorized, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 2).await;
+    let over_balance = reserve * 2 + 1;
+
+    // Program-owned destination with the wrong size
+    let short = Keypair::new();
+    let create_short = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &short.pubkey(),
+        rent.minimum_balance(space as usize - 1),
+        space - 1,
+        &program_id,
+    );
+    // Correctly sized but already Initialized destination
+    let initialized = Keypair::new();
+    let withdrawer = Keypair::new();
+    let create_init = system_instruction::create_account(&ctx.payer.pubkey(), &initialized.pubkey(), reserve, space, &program_id);
+    let init_ix = common::pin_adapter::initialize_checked(
+        &initialized.pubkey(),
+        &Authorized { staker: withdrawer.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[create_short, create_init, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &short, &initialized, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let valid = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+
+    // Over-balance every time; only a valid destination lets it reach the balance check
+    for (dest, expected) in [
+        (short.pubkey(), InstructionError::InvalidAccountData),
+        (initialized.pubkey(), InstructionError::InvalidAccountData),
+        (valid.pubkey(), InstructionError::InsufficientFunds),
+    ] {
+        let ix = ixn::split(&source.pubkey(), &source.pubkey(), over_balance, &dest)
+            .into_iter()
+            .last()
+            .unwrap();
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &source], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(err, TransactionError::InstructionError(0, expected), "destination {}", dest);
+    }
+
+    let src_after = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    assert_eq!(src_after.lamports, reserve * 2);
+}
+
+#[tokio::test]
+async fn split_active_stake_to_exact_minimum_on_both_sides() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::{
+        program_error::ProgramError,
+        stake::{instruction::StakeError, state::Authorized},
+        transaction::TransactionError,
+    };
+    use std::str::FromStr;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    // Active source delegating exactly 2 * min
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve + 2 * min).await;
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space as usize),
+        vote_space,
+        &Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap(),
+    );
+    let init_ix = ixn::initialize_checked(
+        &source.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&source.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create_vote, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &vote, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    common::warp_epochs(&mut ctx, 4).await;
+
+    async fn split_err(ctx: &mut ProgramTestContext, source: &Pubkey, staker: &Keypair, lamports: u64, dest: &Pubkey) -> TransactionError {
+        let ix = ixn::split(source, &staker.pubkey(), lamports, dest).into_iter().last().unwrap();
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, staker], ctx.last_blockhash).unwrap();
+        ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap()
+    }
+    let assert_insufficient_delegation = |err: TransactionError| match err {
+        TransactionError::InstructionError(0, ie) => {
+            let pe = ProgramError::try_from(ie).unwrap();
+            assert!(common::pin_adapter::err::matches_stake_error(&pe, StakeError::InsufficientDelegation), "got {:?}", pe);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    };
+
+    // Destination one short: prefunded reserve + 1 leaves the balance check satisfied
+    let short_dest = create_uninitialized_stake(&mut ctx, &program_id, reserve + 1).await;
+    assert_insufficient_delegation(split_err(&mut ctx, &source.pubkey(), &staker, min - 1, &short_dest.pubkey()).await);
+
+    // Source one short: an undelegated lamport keeps the source balance at its minimum
+    transfer(&mut ctx, &source.pubkey(), 1).await;
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+    assert_insufficient_delegation(split_err(&mut ctx, &source.pubkey(), &staker, min + 1, &dest.pubkey()).await);
+
+    // Exactly min on both sides
+    let ix = ixn::split(&source.pubkey(), &staker.pubkey(), min, &dest.pubkey()).into_iter().last().unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    for (key, lamports) in [(source.pubkey(), reserve + min + 1), (dest.pubkey(), reserve + min)] {
+        let acct = ctx.banks_client.get_account(key).await.unwrap().unwrap();
+        assert_eq!(acct.lamports, lamports);
+        match SS::deserialize(&acct.data).unwrap() {
+            SS::Stake(_, s, _) => assert_eq!(u64::from_le_bytes(s.delegation.stake), min),
+            other => panic!("expected Stake, got {:?}", other),
+        }
+    }
+}
+
+// Unlike the active case above, an Initialized source only has to keep its rent reserve:
+// amounts far below the minimum delegation split freely on both sides
+#[tokio::test]
+async fn split_initialized_stake_enforces_rent_not_minimum_delegation() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::{
+        instruction::InstructionError,
+        stake::state::Authorized,
+        transaction::TransactionError,
+    };
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let reserve = rent.minimum_balance(SS::ACCOUNT_SIZE);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+    assert!(min > 100);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve + 100).await;
+    let init_ix = ixn::initialize_checked(
+        &source.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+
+    // One lamport past the source reserve: rent is the only floor, and it is enforced
+    let ix = ixn::split(&source.pubkey(), &staker.pubkey(), 101, &dest.pubkey()).into_iter().last().unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+
+    // 100 lamports (well under the minimum delegation) leaves exactly the reserve behind
+    let ix = ixn::split(&source.pubkey(), &staker.pubkey(), 100, &dest.pubkey()).into_iter().last().unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    for (key, lamports) in [(source.pubkey(), reserve), (dest.pubkey(), reserve + 100)] {
+        let acct = ctx.banks_client.get_account(key).await.unwrap().unwrap();
+        assert_eq!(acct.lamports, lamports);
+        match SS::deserialize(&acct.data).unwrap() {
+            SS::Initialized(meta) => assert_eq!(u64::from_le_bytes(meta.rent_exempt_reserve), reserve),
+            other => panic!("expected Initialized, got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn split_destination_inherits_source_lockup() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::stake::state::{Authorized, Lockup};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let reserve = rent.minimum_balance(SS::ACCOUNT_SIZE);
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+
+    // Lockup in force on both axes, with a custodian
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let custodian = Pubkey::new_unique();
+    let lockup = Lockup {
+        unix_timestamp: clock.unix_timestamp + 1_000_000,
+        epoch: clock.epoch + 100,
+        custodian,
+    };
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 3).await;
+    let init_ix = ixn::initialize(
+        &source.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+        &lockup,
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+    let ix = ixn::split(&source.pubkey(), &staker.pubkey(), reserve, &dest.pubkey()).into_iter().last().unwrap();
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let src_acct = ctx.banks_client.get_account(source.pubkey()).await.unwrap().unwrap();
+    let dst_acct = ctx.banks_client.get_account(dest.pubkey()).await.unwrap().unwrap();
+    let (SS::Initialized(src_meta), SS::Initialized(dst_meta)) =
+        (SS::deserialize(&src_acct.data).unwrap(), SS::deserialize(&dst_acct.data).unwrap())
+    else {
+        panic!("expected both Initialized");
+    };
+    assert_eq!(dst_meta.lockup, src_meta.lockup);
+    assert_eq!(dst_meta.lockup.custodian, custodian.to_bytes());
+    assert_eq!(dst_meta.lockup.epoch, lockup.epoch);
+    assert_eq!(dst_meta.lockup.unix_timestamp, lockup.unix_timestamp);
+    assert_eq!(dst_meta.authorized, src_meta.authorized);
+}
+
+#[tokio::test]
+async fn split_tolerates_trailing_accounts() {
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    use solana_sdk::{
+        instruction::{AccountMeta, InstructionError},
+        stake::state::Authorized,
+        transaction::TransactionError,
+    };
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let reserve = rent.minimum_balance(SS::ACCOUNT_SIZE);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let source = create_uninitialized_stake(&mut ctx, &program_id, reserve * 3).await;
+    let init_ix = ixn::initialize_checked(
+        &source.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    let dest = create_uninitialized_stake(&mut ctx, &program_id, reserve).await;
+
+    // The staker trailing as a signer does not stand in for a wrong index-2 authority
+    let impostor = Keypair::new();
+    let mut ix = ixn::split(&source.pubkey(), &impostor.pubkey(), reserve, &dest.pubkey()).into_iter().last().unwrap();
+    ix.accounts.push(AccountMeta::new_readonly(staker.pubkey(), true));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &impostor, &staker], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    // Extra readonly accounts after the authority are ignored
+    let mut ix = ixn::split(&source.pubkey(), &staker.pubkey(), reserve, &dest.pubkey()).into_iter().last().unwrap();
+    ix.accounts.push(AccountMeta::new_readonly(solana_sdk::system_program::id(), false));
+    ix.accounts.push(AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    for (key, lamports) in [(source.pubkey(), reserve * 2), (dest.pubkey(), reserve * 2)] {
+        let acct = ctx.banks_client.get_account(key).await.unwrap().unwrap();
+        assert_eq!(acct.lamports, lamports);
+        match SS::deserialize(&acct.data).unwrap() {
+            SS::Initialized(meta) => assert_eq!(meta.authorized.staker, staker.pubkey().to_bytes()),
+            other => panic!("expected Initialized, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Adapter state conversion: pinocchio <-> SDK round trips
+
+mod common;
+use common::pin_adapter::{meta_from_sdk, meta_to_sdk, stake_from_sdk, stake_to_sdk};
+use solana_sdk::{
+    pubkey::Pubkey,
+    stake::state::{Authorized, Delegation, Lockup, Meta, Stake},
+};
+
+fn sample_meta() -> Meta {
+    Meta {
+        rent_exempt_reserve: 2_282_880,
+        authorized: Authorized { staker: Pubkey::new_unique(), withdrawer: Pubkey::new_unique() },
+        lockup: Lockup { unix_timestamp: -42, epoch: 7, custodian: Pubkey::new_unique() },
+    }
+}
+
+#[test]
+fn meta_and_stake_round_trip() {
+    let meta = sample_meta();
+    let pin_meta = meta_from_sdk(&meta);
+    // Roles stay in place through the byte representation
+    assert_eq!(pin_meta.authorized.staker, meta.authorized.staker.to_bytes());
+    assert_eq!(pin_meta.authorized.withdrawer, meta.authorized.withdrawer.to_bytes());
+    assert_eq!(meta_to_sdk(&pin_meta), meta);
+
+    #[allow(deprecated)]
+    let stake = Stake {
+        delegation: Delegation {
+            voter_pubkey: Pubkey::new_unique(),
+            stake: 123_456_789,
+            activation_epoch: 3,
+            deactivation_epoch: u64::MAX,
+            warmup_cooldown_rate: 0.25,
+        },
+        credits_observed: 99,
+    };
+    assert_eq!(stake_to_sdk(&stake_from_sdk(&stake)), stake);
+}
+
+#[test]
+fn serialized_state_decodes_to_same_sdk_values() {
+    use pinocchio_stake::state::{stake_flag::StakeFlags, stake_state_v2::StakeStateV2};
+    let meta = sample_meta();
+    let stake = Stake {
+        delegation: Delegation::new(&Pubkey::new_unique(), 5_000, 11),
+        credits_observed: 1,
+    };
+    let bytes = common::pin_adapter::encode_program_stake_state(&StakeStateV2::Stake(
+        meta_from_sdk(&meta),
+        stake_from_sdk(&stake),
+        StakeFlags::empty(),
+    ));
+    match StakeStateV2::deserialize(&bytes).unwrap() {
+        StakeStateV2::Stake(m, s, _) => {
+            assert_eq!(meta_to_sdk(&m), meta);
+            assert_eq!(stake_to_sdk(&s), stake);
+        }
+        other => panic!("expected Stake state, got {:?}", other),
+    }
+}
+
+#[test]
+fn assert_states_eq_names_differing_fields() {
+    use common::pin_adapter::{assert_states_eq, state_from_sdk};
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2;
+    use solana_sdk::stake::{stake_flags::StakeFlags, state::StakeStateV2 as SdkState};
+
+    let meta = sample_meta();
+    let stake = Stake { delegation: Delegation::new(&Pubkey::new_unique(), 5_000, 11), credits_observed: 1 };
+    let sdk = SdkState::Stake(meta, stake, StakeFlags::empty());
+    let state = state_from_sdk(&sdk);
+    assert!(matches!(state, StakeStateV2::Stake(..)));
+    assert_states_eq(&state, &state.clone());
+
+    let mut moved = meta;
+    moved.lockup.epoch += 1;
+    let other = state_from_sdk(&SdkState::Stake(moved, stake, StakeFlags::empty()));
+    let msg = std::panic::catch_unwind(|| assert_states_eq(&state, &other))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(msg.contains("meta.lockup.epoch: 7 != 8"), "{}", msg);
+    assert!(!msg.contains("meta.authorized"), "{}", msg);
+
+    let msg = std::panic::catch_unwind(|| {
+        assert_states_eq(&state, &state_from_sdk(&SdkState::Initialized(meta)))
+    })
+    .unwrap_err()
+    .downcast::<String>()
+    .unwrap();
+    assert!(msg.contains("variant: \"Stake\" != \"Initialized\""), "{}", msg);
+}
@@ -48,3 +48,44 @@ async fn corrupted_variant_tag_is_invalid() {
     let res = ctx.banks_client.process_transaction(tx).await;
     assert!(matches!(&res, Err(e) if is_invalid_or_neak(e)), "expected InvalidInstructionData/NotEnoughAccountKeys, got {:?}", res);
 }
+
+#[tokio::test]
+async fn single_zero_byte_is_not_initialize() {
+    use solana_sdk::instruction::{AccountMeta, InstructionError};
+    use solana_sdk::transaction::TransactionError;
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+    let mut ctx = common::program_test().start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    // A real, writable Uninitialized stake account, so only the payload can be at fault
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let create = solana_sdk::system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        rent.minimum_balance(SS::ACCOUNT_SIZE),
+        SS::ACCOUNT_SIZE as u64,
+        &program_id,
+    );
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(stake.pubkey(), false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false),
+        ],
+        data: vec![0u8],
+    };
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidInstructionData));
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(SS::deserialize(&acct.data).unwrap(), SS::Uninitialized));
+}
@@ -302,3 +302,786 @@ async fn withdraw_stake_after_deactivate_full_succeeds() {
         assert!(matches!(state, pinocchio_stake::state::stake_state_v2::StakeStateV2::Uninitialized));
     }
 }
+
+#[tokio::test]
+async fn withdraw_initialized_lockup_in_force_requires_custodian() {
+    use solana_sdk::{instruction::InstructionError, program_error::ProgramError, stake::state::Lockup, transaction::TransactionError};
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let custodian = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Initialize with a lockup far in the future
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let lockup = Lockup { unix_timestamp: 0, epoch: clock.epoch + 100, custodian: custodian.pubkey() };
+    let init_ix = ixn::initialize(
+        &stake_acc.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+        &lockup,
+    );
+    let msg = Message::new(&[init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let extra: u64 = 1_000_000;
+    transfer(&mut ctx, &stake_acc.pubkey(), extra).await;
+
+    // Withdrawer alone: LockupInForce
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), extra, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    let TransactionError::InstructionError(_, ie) = err else { panic!("unexpected error: {:?}", err) };
+    let pe = ProgramError::try_from(ie).unwrap();
+    assert!(common::pin_adapter::err::matches_stake_error(&pe, solana_sdk::stake::instruction::StakeError::LockupInForce), "got {:?}", pe);
+
+    // Custodian co-signs: reserve is still enforced (Initialized reserve is rent only, nothing staked)
+    refresh_blockhash(&mut ctx).await;
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), extra + 1, Some(&custodian.pubkey()));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer, &custodian], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+
+    // Custodian co-signs for exactly the lamports above the reserve: succeeds
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), extra, Some(&custodian.pubkey()));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer, &custodian], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve);
+}
+
+#[tokio::test]
+async fn withdraw_uninitialized_full_with_stake_account_as_sole_authority() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // The stake account is both source (index 0) and withdraw authority; it is the only
+    // signer besides the fee payer
+    let recipient = Keypair::new();
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &stake_acc.pubkey(), &recipient.pubkey(), reserve, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    let res = ctx.banks_client.process_transaction(tx).await;
+    assert!(res.is_ok(), "Uninitialized full withdraw signed by the stake account should succeed: {:?}", res);
+
+    let recipient_acc = ctx.banks_client.get_account(recipient.pubkey()).await.unwrap().unwrap();
+    assert_eq!(recipient_acc.lamports, reserve);
+    if let Some(after) = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap() {
+        assert_eq!(after.lamports, 0);
+    }
+}
+
+#[tokio::test]
+async fn withdraw_at_deactivation_epoch_keeps_full_stake_locked() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let delegated: u64 = common::get_minimum_delegation_lamports(&mut ctx).await;
+    let slack: u64 = 1_000_000;
+
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        reserve + delegated,
+        space,
+        &program_id,
+    );
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space as usize),
+        vote_space,
+        &vote_program_id,
+    );
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create, create_vote, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &vote, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Let the delegation fully activate, then add undelegated lamports on top
+    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
+    let mut root_slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for _ in 0..4 {
+        root_slot += slots_per_epoch;
+        ctx.warp_to_slot(root_slot).unwrap();
+    }
+    refresh_blockhash(&mut ctx).await;
+    transfer(&mut ctx, &stake.pubkey(), slack).await;
+
+    let deact_ix = ixn::deactivate_stake(&stake.pubkey(), &staker.pubkey());
+    let msg = Message::new(&[deact_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Still in the deactivation epoch: cooldown has not started
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_, s, _) => {
+            assert_eq!(u64::from_le_bytes(s.delegation.deactivation_epoch), clock.epoch);
+        }
+        other => panic!("expected Stake, got {:?}", other),
+    }
+
+    // One lamport past the slack would dip into delegated + reserve
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), slack + 1, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::InsufficientFunds
+        )
+    );
+
+    // Exactly the slack is withdrawable, leaving the floor intact
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), slack, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, delegated + reserve);
+}
+
+#[tokio::test]
+async fn withdraw_all_but_reserve_after_cooldown() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let delegated: u64 = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let msg = Message::new(
+        &[
+            system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + delegated, space, &program_id),
+            system_instruction::create_account(
+                &ctx.payer.pubkey(),
+                &vote.pubkey(),
+                rent.minimum_balance(vote_space as usize),
+                vote_space,
+                &vote_program_id,
+            ),
+            ixn::initialize_checked(
+                &stake.pubkey(),
+                &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+            ),
+            ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey()),
+        ],
+        Some(&ctx.payer.pubkey()),
+    );
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &vote, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Activate, deactivate, then cool down
+    let slots_per_epoch = ctx.genesis_config().epoch_schedule.slots_per_epoch;
+    let mut root_slot = ctx.banks_client.get_root_slot().await.unwrap();
+    for _ in 0..4 {
+        root_slot += slots_per_epoch;
+        ctx.warp_to_slot(root_slot).unwrap();
+    }
+    refresh_blockhash(&mut ctx).await;
+    let deact_ix = ixn::deactivate_stake(&stake.pubkey(), &staker.pubkey());
+    let msg = Message::new(&[deact_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    for _ in 0..4 {
+        root_slot += slots_per_epoch;
+        ctx.warp_to_slot(root_slot).unwrap();
+    }
+    refresh_blockhash(&mut ctx).await;
+
+    // Effective stake is now zero: everything above the reserve is withdrawable
+    let before = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    let free = before.lamports - reserve;
+    assert_eq!(free, delegated);
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), free, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Partial withdraw leaves the (deactivated) Stake state in place
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve);
+    match pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&after.data).unwrap() {
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(_, s, _) => {
+            assert_ne!(u64::from_le_bytes(s.delegation.deactivation_epoch), u64::MAX);
+        }
+        other => panic!("expected Stake, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn withdraw_custodian_equal_to_withdrawer_signs_once() {
+    use solana_sdk::{program_error::ProgramError, stake::state::Lockup, transaction::TransactionError};
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let staker = Keypair::new();
+    // Custodied stake: one key is both withdraw authority and lockup custodian
+    let withdrawer = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    let lockup = Lockup { unix_timestamp: 0, epoch: clock.epoch + 100, custodian: withdrawer.pubkey() };
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let init_ix = ixn::initialize(
+        &stake_acc.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+        &lockup,
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let extra: u64 = 1_000_000;
+    transfer(&mut ctx, &stake_acc.pubkey(), extra).await;
+
+    // Custodian slot omitted: the withdraw-authority slot alone does not lift the lockup
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), extra, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    let TransactionError::InstructionError(_, ie) = err else { panic!("unexpected error: {:?}", err) };
+    let pe = ProgramError::try_from(ie).unwrap();
+    assert!(common::pin_adapter::err::matches_stake_error(&pe, solana_sdk::stake::instruction::StakeError::LockupInForce), "got {:?}", pe);
+
+    // Same key passed as custodian: the one signature covers both roles
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), extra, Some(&withdrawer.pubkey()));
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve);
+}
+
+#[tokio::test]
+async fn withdraw_to_fresh_system_account_credits_recipient() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    // Enough for the recipient to come into existence rent-exempt
+    let amount = rent.minimum_balance(0) + 1_000;
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve + amount, space, &program_id);
+    let init_ix = ixn::initialize_checked(&stake_acc.pubkey(), &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() });
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Recipient has never been funded: no owner check applies to the destination
+    let recipient = Pubkey::new_unique();
+    assert!(ctx.banks_client.get_account(recipient).await.unwrap().is_none());
+
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &recipient, amount, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let dest = ctx.banks_client.get_account(recipient).await.unwrap().unwrap();
+    assert_eq!(dest.lamports, amount);
+    assert_eq!(dest.owner, solana_sdk::system_program::id());
+    let src = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert_eq!(src.lamports, reserve);
+}
+
+// Full close of an Initialized account without a lockup takes the fast path and is
+// cheaper than the general path over an account with an expired lockup
+#[tokio::test]
+async fn withdraw_full_close_without_lockup_is_cheaper() {
+    use solana_sdk::stake::state::Lockup;
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let authorized = Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() };
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let lamports = rent.minimum_balance(space as usize) + 1_000_000;
+
+    // Same balance and authorities; only the second carries an (expired) lockup
+    let plain = Keypair::new();
+    let locked = Keypair::new();
+    let expired = Lockup { unix_timestamp: 0, epoch: 0, custodian: Pubkey::new_unique() };
+    let ixs = [
+        system_instruction::create_account(&ctx.payer.pubkey(), &plain.pubkey(), lamports, space, &program_id),
+        ixn::initialize_checked(&plain.pubkey(), &authorized),
+        system_instruction::create_account(&ctx.payer.pubkey(), &locked.pubkey(), lamports, space, &program_id),
+        ixn::initialize(&locked.pubkey(), &authorized, &expired),
+    ];
+    let msg = Message::new(&ixs, Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &plain, &withdrawer, &locked], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let recipient = Pubkey::new_unique();
+    let mut units = Vec::new();
+    for stake in [plain.pubkey(), locked.pubkey()] {
+        let ix = ixn::withdraw(&stake, &withdrawer.pubkey(), &recipient, lamports, None);
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+        let sim = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+        assert!(matches!(sim.result, Some(Ok(()))), "simulation failed: {:?}", sim.result);
+        units.push(sim.simulation_details.expect("simulation details").units_consumed);
+    }
+    assert!(units[0] < units[1], "fast close {} CU, general close {} CU", units[0], units[1]);
+
+    // The fast path still closes the account
+    let ix = ixn::withdraw(&plain.pubkey(), &withdrawer.pubkey(), &recipient, lamports, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    assert!(ctx.banks_client.get_account(plain.pubkey()).await.unwrap().is_none());
+    assert_eq!(ctx.banks_client.get_account(recipient).await.unwrap().unwrap().lamports, lamports);
+}
+
+#[tokio::test]
+async fn withdraw_full_only_after_cooldown_completes() {
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let delegated: u64 = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let create = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &stake.pubkey(),
+        reserve + delegated,
+        space,
+        &program_id,
+    );
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space as usize),
+        vote_space,
+        &vote_program_id,
+    );
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create, create_vote, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &vote, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    warp_epochs(&mut ctx, 4).await;
+
+    let deact_ix = ixn::deactivate_stake(&stake.pubkey(), &staker.pubkey());
+    let msg = Message::new(&[deact_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Still cooling down: the delegation is staked, so a full withdraw is refused
+    let full = reserve + delegated;
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), full, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        solana_sdk::transaction::TransactionError::InstructionError(
+            0,
+            solana_sdk::instruction::InstructionError::InsufficientFunds
+        )
+    );
+
+    // Cooldown complete: staked == 0, so the entire balance (reserve included) may leave
+    warp_epochs(&mut ctx, 2).await;
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert!(matches!(
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap(),
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Stake(..)
+    ));
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), acct.lamports, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Zero-lamport accounts may be purged; if still visible it must be Uninitialized
+    if let Some(after) = ctx.banks_client.get_account(stake.pubkey()).await.unwrap() {
+        assert_eq!(after.lamports, 0);
+        assert!(matches!(
+            pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&after.data).unwrap(),
+            pinocchio_stake::state::stake_state_v2::StakeStateV2::Uninitialized
+        ));
+    }
+}
+
+#[tokio::test]
+async fn withdraw_initialized_partial_stops_exactly_at_reserve() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake = Keypair::new();
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + 100, space, &program_id);
+    let init_ix = ixn::initialize_checked(&stake.pubkey(), &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() });
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // 101 would leave reserve - 1 behind
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), 101, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+
+    // 100 leaves exactly the reserve
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), 100, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(acct.lamports, reserve);
+    assert!(matches!(
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::deserialize(&acct.data).unwrap(),
+        pinocchio_stake::state::stake_state_v2::StakeStateV2::Initialized(_)
+    ));
+}
+
+#[tokio::test]
+async fn withdraw_expired_lockup_needs_only_withdrawer() {
+    use solana_sdk::stake::state::Lockup;
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let withdrawer = Keypair::new();
+    let custodian = Keypair::new();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+
+    // Lockup with a custodian, both bounds strictly in the past
+    warp_epochs(&mut ctx, 2).await;
+    let clock = ctx.banks_client.get_sysvar::<solana_sdk::clock::Clock>().await.unwrap();
+    assert!(clock.epoch > 0);
+    let lockup = Lockup {
+        unix_timestamp: clock.unix_timestamp - 1,
+        epoch: clock.epoch - 1,
+        custodian: custodian.pubkey(),
+    };
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve, space, &program_id);
+    let init_ix = ixn::initialize(
+        &stake_acc.pubkey(),
+        &Authorized { staker: Pubkey::new_unique(), withdrawer: withdrawer.pubkey() },
+        &lockup,
+    );
+    let msg = Message::new(&[create, init_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let extra: u64 = 1_000_000;
+    transfer(&mut ctx, &stake_acc.pubkey(), extra).await;
+
+    // Withdrawer alone, no custodian meta: the expired lockup does not block
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), extra, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve);
+}
+
+// Uninitialized source: only the stake account's own signature authorizes, and a
+// partial withdraw must leave the rent reserve behind
+#[tokio::test]
+async fn withdraw_uninitialized_partial_keeps_reserve_and_needs_source_signature() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let stake_acc = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = pinocchio_stake::state::stake_state_v2::StakeStateV2::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let extra: u64 = 1_000_000;
+
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake_acc.pubkey(), reserve + extra, space, &program_id);
+    let msg = Message::new(&[create], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Another key in the withdraw-authority slot does not stand in for the source
+    let other = Keypair::new();
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &other.pubkey(), &ctx.payer.pubkey(), extra, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &other], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    // One lamport into the reserve
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &stake_acc.pubkey(), &ctx.payer.pubkey(), extra + 1, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+
+    // Everything above the reserve
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &stake_acc.pubkey(), &ctx.payer.pubkey(), extra, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    let after = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, reserve);
+
+    // Then the rest, closing the account
+    let recipient = Pubkey::new_unique();
+    let ix = ixn::withdraw(&stake_acc.pubkey(), &stake_acc.pubkey(), &recipient, reserve, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &stake_acc], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+    let credited = ctx.banks_client.get_account(recipient).await.unwrap().unwrap();
+    assert_eq!(credited.lamports, reserve);
+    if let Some(closed) = ctx.banks_client.get_account(stake_acc.pubkey()).await.unwrap() {
+        assert_eq!(closed.lamports, 0);
+    }
+}
+
+#[tokio::test]
+async fn withdraw_full_refused_while_staked_below_reserve() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space as usize),
+        vote_space,
+        &vote_program_id,
+    );
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create_vote, create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &vote, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // Drop the balance below the reserve while the delegation stays in place
+    let mut acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    let underfunded = reserve - 1;
+    acct.lamports = underfunded;
+    ctx.set_account(&stake.pubkey(), &acct.into());
+
+    // Nothing is free, yet the account still holds a delegation: it cannot be closed
+    let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), underfunded, None);
+    let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+    let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, underfunded);
+    assert!(matches!(SS::deserialize(&after.data).unwrap(), SS::Stake(..)));
+}
+
+#[tokio::test]
+async fn withdraw_partial_refused_from_underfunded_stake() {
+    use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+    use pinocchio_stake::state::stake_state_v2::StakeStateV2 as SS;
+
+    let mut pt = common::program_test();
+    let mut ctx = pt.start_with_context().await;
+    let program_id = Pubkey::new_from_array(pinocchio_stake::ID);
+
+    let staker = Keypair::new();
+    let withdrawer = Keypair::new();
+    let stake = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = SS::ACCOUNT_SIZE as u64;
+    let reserve = rent.minimum_balance(space as usize);
+    let min = common::get_minimum_delegation_lamports(&mut ctx).await;
+
+    let vote = Keypair::new();
+    let vote_space = std::mem::size_of::<pinocchio_stake::state::vote_state::VoteState>() as u64;
+    let vote_program_id = Pubkey::from_str("Vote111111111111111111111111111111111111111").unwrap();
+    let create_vote = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &vote.pubkey(),
+        rent.minimum_balance(vote_space as usize),
+        vote_space,
+        &vote_program_id,
+    );
+    let create = system_instruction::create_account(&ctx.payer.pubkey(), &stake.pubkey(), reserve + min, space, &program_id);
+    let init_ix = ixn::initialize_checked(
+        &stake.pubkey(),
+        &Authorized { staker: staker.pubkey(), withdrawer: withdrawer.pubkey() },
+    );
+    let del_ix = ixn::delegate_stake(&stake.pubkey(), &staker.pubkey(), &vote.pubkey());
+    let msg = Message::new(&[create_vote, create, init_ix, del_ix], Some(&ctx.payer.pubkey()));
+    let mut tx = Transaction::new_unsigned(msg);
+    tx.try_sign(&[&ctx.payer, &vote, &stake, &withdrawer, &staker], ctx.last_blockhash).unwrap();
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    // One lamport short of reserve + delegation
+    let mut acct = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    let underfunded = reserve + min - 1;
+    acct.lamports = underfunded;
+    ctx.set_account(&stake.pubkey(), &acct.into());
+
+    // Every partial withdraw is refused, including zero lamports
+    for amount in [1u64, 0] {
+        let ix = ixn::withdraw(&stake.pubkey(), &withdrawer.pubkey(), &ctx.payer.pubkey(), amount, None);
+        let msg = Message::new(&[ix], Some(&ctx.payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(msg);
+        tx.try_sign(&[&ctx.payer, &withdrawer], ctx.last_blockhash).unwrap();
+        let err = ctx.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+        assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InsufficientFunds), "amount {}", amount);
+    }
+
+    let after = ctx.banks_client.get_account(stake.pubkey()).await.unwrap().unwrap();
+    assert_eq!(after.lamports, underfunded);
+}